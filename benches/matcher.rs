@@ -0,0 +1,87 @@
+//! Block-matcher benchmarks on synthetic trees.
+//!
+//! `cargo bench --bench matcher` — the 10k-file case is the one that used to
+//! go quadratic (common lines like `return None` fanned out to every file).
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use yggdrasil_cli::cli::{find_block_matches_multi, MatchMode};
+
+/// Tiny deterministic PRNG so runs are comparable.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+}
+
+fn function(rng: &mut Lcg, file: usize, n: usize) -> String {
+    let mut body = format!("def fn_{}_{}(arg):\n", file, n);
+    for k in 0..(3 + rng.next() % 6) {
+        body.push_str(&format!("    v{} = arg * {} + {}\n", k, rng.next() % 97, rng.next() % 1000));
+    }
+    // boilerplate shared by every file
+    body.push_str("    if arg is None:\n        return None\n    return v0\n\n");
+    body
+}
+
+type Tree = Vec<(String, String)>;
+
+/// `files` Python-like modules, then the same tree with ~5% of functions
+/// moved to another module and a few lines edited.
+fn synthetic_tree(files: usize) -> (Tree, Tree) {
+    let mut rng = Lcg(42);
+    let modules: Vec<Vec<String>> = (0..files)
+        .map(|f| (0..4).map(|n| function(&mut rng, f, n)).collect())
+        .collect();
+
+    let from = modules
+        .iter()
+        .enumerate()
+        .map(|(f, funcs)| (format!("old/m{}.py", f), funcs.concat()))
+        .collect();
+
+    let mut moved = modules.clone();
+    for f in 0..files {
+        if rng.next().is_multiple_of(20) {
+            let func = moved[f].remove(0);
+            let dest = (rng.next() as usize) % files;
+            moved[dest].push(func);
+        }
+        if rng.next().is_multiple_of(10) {
+            if let Some(func) = moved[f].first_mut() {
+                *func = func.replacen("arg *", "arg /", 1);
+            }
+        }
+    }
+
+    let to = moved
+        .iter()
+        .enumerate()
+        .map(|(f, funcs)| (format!("new/m{}.py", f), funcs.concat()))
+        .collect();
+
+    (from, to)
+}
+
+fn bench_matcher(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find_block_matches_multi");
+    group.sample_size(10);
+
+    for files in [100, 1_000, 10_000] {
+        let (from, to) = synthetic_tree(files);
+        for mode in [MatchMode::Exact, MatchMode::Identifiers] {
+            let id = BenchmarkId::new(format!("{:?}", mode).to_lowercase(), files);
+            group.bench_with_input(id, &(&from, &to), |b, (from, to)| {
+                b.iter(|| find_block_matches_multi(from, to, mode))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_matcher);
+criterion_main!(benches);
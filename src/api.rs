@@ -0,0 +1,171 @@
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::snapshot::options::{Format, SnapshotOptions};
+use crate::snapshot::run::{render_shards, select_files, Shard, Sniffed};
+use crate::types::FileEntry;
+
+/// A configured snapshot. Build one with [`Snapshot::builder`].
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    options: SnapshotOptions,
+}
+
+/// The result of [`Snapshot::render`].
+#[derive(Debug, Clone)]
+pub struct Rendered {
+    /// Every selected file, in codex order
+    pub files: Vec<FileEntry>,
+    /// What `sniff` discovered, if it was used
+    pub sniffed: Option<Sniffed>,
+    /// One shard, or several when splitting
+    pub shards: Vec<Shard>,
+}
+
+impl Rendered {
+    /// All shards concatenated (the whole codex when not splitting).
+    pub fn bytes(&self) -> Vec<u8> {
+        self.shards.iter().flat_map(|s| s.bytes.iter().copied()).collect()
+    }
+}
+
+impl Snapshot {
+    pub fn builder() -> SnapshotBuilder {
+        SnapshotBuilder::default()
+    }
+
+    /// Wrap fully-resolved options (this is what the CLI does).
+    pub fn from_options(options: SnapshotOptions) -> Self {
+        Snapshot { options }
+    }
+
+    pub fn options(&self) -> &SnapshotOptions {
+        &self.options
+    }
+
+    /// Resolve the selection (running sniff if configured) without rendering.
+    pub fn files(&self) -> Result<Vec<FileEntry>> {
+        select_files(&self.options).map(|(files, _)| files)
+    }
+
+    /// Select and render. Nothing is written to disk or stdout (unless the
+    /// stats cache was turned on with [`SnapshotBuilder::cache`], which
+    /// writes to the user's cache directory).
+    pub fn render(&self) -> Result<Rendered> {
+        let (files, sniffed) = select_files(&self.options)?;
+        let shards = render_shards(&self.options, files.clone(), sniffed.as_ref())?;
+        Ok(Rendered { files, sniffed, shards })
+    }
+}
+
+/// Builder for [`Snapshot`].
+///
+/// ```no_run
+/// use yggdrasil_cli::{Format, Snapshot};
+///
+/// let codex = Snapshot::builder()
+///     .root(".")
+///     .only(["src", "README.md"])
+///     .format(Format::Markdown)
+///     .contents(true)
+///     .build()?
+///     .render()?;
+///
+/// std::fs::write("SHOW.md", codex.bytes())?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct SnapshotBuilder {
+    options: SnapshotOptions,
+}
+
+impl Default for SnapshotBuilder {
+    fn default() -> Self {
+        let options = SnapshotOptions { cache: false, ..SnapshotOptions::default() };
+        SnapshotBuilder { options }
+    }
+}
+
+impl SnapshotBuilder {
+    /// Root directory to scan (default `.`).
+    pub fn root(mut self, dir: impl Into<String>) -> Self {
+        self.options.dir = dir.into();
+        self
+    }
+
+    /// Restrict to these files, directories or globs.
+    pub fn only<I, S>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options.only.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Keep only files with these extensions.
+    pub fn show<I, S>(mut self, exts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options.show.extend(exts.into_iter().map(Into::into));
+        self
+    }
+
+    /// Skip paths matching these patterns.
+    pub fn ignore<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options.ignore.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Expand an entry file into its local import closure.
+    pub fn sniff(mut self, entry: impl Into<String>) -> Self {
+        self.options.sniff = Some(entry.into());
+        self
+    }
+
+    pub fn format(mut self, format: Format) -> Self {
+        self.options.format = format;
+        self
+    }
+
+    /// Include file contents, not just the index.
+    pub fn contents(mut self, yes: bool) -> Self {
+        self.options.contents = yes;
+        self
+    }
+
+    /// Split into shards of roughly `tokens` tokens each.
+    pub fn split(mut self, tokens: usize) -> Self {
+        self.options.split_tokens = Some(tokens);
+        self
+    }
+
+    /// Use the stats cache in `$XDG_CACHE_HOME/ygg` (default off; the CLI
+    /// turns it on).
+    pub fn cache(mut self, yes: bool) -> Self {
+        self.options.cache = yes;
+        self
+    }
+
+    /// Record an output path on each shard (`<stem>.shardNN.md` when splitting).
+    pub fn out(mut self, path: impl Into<String>) -> Self {
+        self.options.out = Some(path.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Snapshot> {
+        if !Path::new(&self.options.dir).is_dir() {
+            return Err(Error::RootNotFound(self.options.dir));
+        }
+        if self.options.split_tokens == Some(0) {
+            return Err(Error::InvalidOption("split size must be greater than zero".into()));
+        }
+        Ok(Snapshot { options: self.options })
+    }
+}
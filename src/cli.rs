@@ -0,0 +1,17 @@
+//! What the `ygg` binary and the benches reach into. Not part of the
+//! library API: no stability promise, and subject to change with the CLI.
+
+pub use crate::diff::matcher::find_block_matches_multi;
+pub use crate::diff::{
+    run_diff, DiffAlgorithm, DiffFormat, DiffOptions, Emphasis, FailOn, HunkOptions, MatchMode,
+};
+pub use crate::merge::{print_report as print_merge_report, run_merge, MergeOptions, MergedFile};
+pub use crate::scanner::patterns::load_patterns_file;
+pub use crate::scanner::run_cache;
+pub use crate::scanner::selection::Selection;
+pub use crate::scanner::stdin::read_multiline_stdin;
+pub use crate::snapshot::run::announce_sniff;
+pub use crate::snapshot::run_snapshot;
+pub use crate::store::{print_log, run_log, run_show, run_snap};
+pub use crate::verify::run_verify;
+pub use crate::watch::run_watch;
@@ -0,0 +1,97 @@
+use blake3;
+
+/// Header key carrying the whole-codex seal.
+pub const SEAL_KEY: &str = "codex_hash: ";
+
+/// Content hash of a single file (full BLAKE3, hex).
+pub fn content_hash(text: &str) -> String {
+    blake3::hash(text.as_bytes()).to_hex().to_string()
+}
+
+/// Short form of a content hash for index columns.
+pub fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(12)]
+}
+
+/// Seal a finished Markdown codex.
+///
+/// The hash covers the whole document as written, then a `codex_hash:` line is
+/// inserted right after the `format:` header. Verification removes that line
+/// and re-hashes, so any edit anywhere else breaks the seal.
+///
+/// Plain-text output has no header block and is returned unchanged.
+pub fn seal_codex(text: &str) -> String {
+    let Some(pos) = text.find("\nformat: ") else {
+        return text.to_string();
+    };
+
+    let hash = content_hash(text);
+    let line_end = text[pos + 1..]
+        .find('\n')
+        .map(|i| pos + 1 + i + 1)
+        .unwrap_or(text.len());
+
+    format!("{}{}{}\n{}", &text[..line_end], SEAL_KEY, hash, &text[line_end..])
+}
+
+/// Outcome of checking a codex seal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SealStatus {
+    Intact,
+    Tampered { expected: String, actual: String },
+    Missing,
+}
+
+/// Check the `codex_hash:` seal written by [`seal_codex`].
+pub fn check_seal(text: &str) -> SealStatus {
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        if let Some(expected) = line.strip_prefix(SEAL_KEY) {
+            let expected = expected.trim().to_string();
+            let unsealed = format!("{}{}", &text[..offset], &text[offset + line.len()..]);
+            let actual = content_hash(&unsealed);
+
+            return if actual == expected {
+                SealStatus::Intact
+            } else {
+                SealStatus::Tampered { expected, actual }
+            };
+        }
+        offset += line.len();
+    }
+
+    SealStatus::Missing
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "# CODEX\nproject: demo\nformat: markdown\n\n## INDEX\nsrc/a.rs\n";
+
+    #[test]
+    fn test_seal_roundtrip() {
+        let sealed = seal_codex(DOC);
+        assert!(sealed.contains("format: markdown\ncodex_hash: "));
+        assert_eq!(check_seal(&sealed), SealStatus::Intact);
+    }
+
+    #[test]
+    fn test_seal_detects_tampering() {
+        let sealed = seal_codex(DOC).replace("src/a.rs", "src/b.rs");
+        assert!(matches!(check_seal(&sealed), SealStatus::Tampered { .. }));
+    }
+
+    #[test]
+    fn test_unsealed_codex() {
+        assert_eq!(check_seal(DOC), SealStatus::Missing);
+    }
+
+    #[test]
+    fn test_plain_text_is_not_sealed() {
+        let plain = "✨ Project Snapshot: .\n📄 Files\n";
+        assert_eq!(seal_codex(plain), plain);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod integrity;
+pub mod parse;
+
+pub use parse::parse_codex;
@@ -0,0 +1,157 @@
+/// A file block recovered from a Markdown codex.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct CodexFile {
+    pub path: String,
+    pub hash: Option<String>,
+    pub content: String,
+}
+
+/// A parsed codex: its embedded files in document order.
+#[derive(Debug, Clone, Default)]
+pub struct Codex {
+    pub files: Vec<CodexFile>,
+}
+
+/// Pull `key="value"` out of a `<file ...>` tag.
+fn tag_attr(tag: &str, key: &str) -> Option<String> {
+    let needle = format!("{}=\"", key);
+    let start = tag.find(&needle)? + needle.len();
+    let end = tag[start..].find('"')? + start;
+    Some(tag[start..end].to_string())
+}
+
+const CLI_START: &str = "<<< FILE START: ";
+const CLI_END: &str = "<<< FILE END: ";
+
+/// Parse the embedded files of a codex.
+///
+/// Markdown blocks look like:
+///
+/// ```text
+/// <file path="src/main.rs" lang="rust" lines="10" hash="…">
+/// ```rust
+/// …contents…
+/// ```
+/// </file>
+/// ```
+///
+/// The closing fence is the last ```` ``` ```` before `</file>`, so files that
+/// contain fences of their own (e.g. Markdown) survive the round trip.
+///
+/// Plain-text codices use `<<< FILE START: path >>>` / `<<< FILE END: path >>>`
+/// markers instead; those carry no hash.
+pub fn parse_codex(text: &str) -> Codex {
+    let mut files = Vec::new();
+    let mut lines = text.split_inclusive('\n');
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_end();
+
+        if let Some(rest) = trimmed.strip_prefix(CLI_START) {
+            let path = rest.strip_suffix(" >>>").unwrap_or(rest).to_string();
+            let end = format!("{}{} >>>", CLI_END, path);
+
+            let mut content = String::new();
+            for inner in lines.by_ref() {
+                // contents without a final newline run straight into the marker
+                if let Some(body) = inner.trim_end().strip_suffix(end.as_str()) {
+                    content.push_str(body);
+                    break;
+                }
+                content.push_str(inner);
+            }
+
+            files.push(CodexFile { path, hash: None, content });
+            continue;
+        }
+
+        if !trimmed.starts_with("<file ") {
+            continue;
+        }
+
+        let Some(path) = tag_attr(trimmed, "path") else {
+            continue;
+        };
+        let hash = tag_attr(trimmed, "hash");
+
+        // opening fence
+        if lines.next().is_none() {
+            break;
+        }
+
+        let mut body: Vec<&str> = Vec::new();
+        for inner in lines.by_ref() {
+            if inner.trim_end() == "</file>" {
+                break;
+            }
+            body.push(inner);
+        }
+
+        // drop the closing fence
+        if body.last().map(|l| l.trim_end() == "```").unwrap_or(false) {
+            body.pop();
+        }
+
+        files.push(CodexFile {
+            path,
+            hash,
+            content: body.concat(),
+        });
+    }
+
+    Codex { files }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markdown_blocks() {
+        let text = "\
+## FILES
+<file path=\"src/a.rs\" lang=\"rust\" lines=\"1\" hash=\"abc\">
+```rust
+fn a() {}
+```
+</file>
+
+<file path=\"README.md\" lang=\"markdown\" lines=\"3\">
+```markdown
+```bash
+ygg
+```
+```
+</file>
+";
+        let codex = parse_codex(text);
+        assert_eq!(codex.files.len(), 2);
+        assert_eq!(codex.files[0].path, "src/a.rs");
+        assert_eq!(codex.files[0].hash.as_deref(), Some("abc"));
+        assert_eq!(codex.files[0].content, "fn a() {}\n");
+        assert_eq!(codex.files[1].hash, None);
+        assert_eq!(codex.files[1].content, "```bash\nygg\n```\n");
+    }
+
+    #[test]
+    fn test_parse_cli_markers() {
+        let text = "\
+📑 File Contents
+<<< FILE START: src/a.rs >>>
+fn a() {}
+<<< FILE END: src/a.rs >>>
+
+<<< FILE START: src/b.rs >>>
+fn b() {}<<< FILE END: src/b.rs >>>
+
+";
+        let codex = parse_codex(text);
+        assert_eq!(codex.files.len(), 2);
+        assert_eq!(codex.files[0].path, "src/a.rs");
+        assert_eq!(codex.files[0].content, "fn a() {}\n");
+        assert_eq!(codex.files[1].content, "fn b() {}");
+        assert_eq!(codex.files[1].hash, None);
+    }
+}
@@ -0,0 +1,29 @@
+use std::fs;
+
+use crate::codex::parse_codex;
+use crate::error::{Error, Result};
+use crate::types::SourceFile;
+
+/// Load the files embedded in a codex (Markdown or plain-text markers) as one
+/// side of a diff. Codex paths are already root-relative (a leading `./` is
+/// dropped), so they are used both as the pairing key and for display.
+pub fn load_codex_side(path: &str) -> Result<Vec<SourceFile>> {
+    let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+    let codex = parse_codex(&text);
+
+    if codex.files.is_empty() {
+        return Err(Error::InvalidOption(format!(
+            "'{}' contains no embedded files (was it written with --contents?)",
+            path
+        )));
+    }
+
+    Ok(codex
+        .files
+        .into_iter()
+        .map(|f| {
+            let path = f.path.strip_prefix("./").unwrap_or(&f.path).to_string();
+            SourceFile { rel: path.clone(), path, content: f.content }
+        })
+        .collect())
+}
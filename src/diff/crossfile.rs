@@ -31,10 +31,10 @@ pub fn group_and_filter_matches(
                 .collect();
 
             let bhash = hash_block(&slice);
-            if seen_blocks.contains_key(&bhash) {
-                is_addition = true;
+            if let std::collections::hash_map::Entry::Vacant(e) = seen_blocks.entry(bhash) {
+                e.insert(true);
             } else {
-                seen_blocks.insert(bhash, true);
+                is_addition = true;
             }
         }
 
@@ -46,7 +46,14 @@ pub fn group_and_filter_matches(
 
     grouped
         .into_iter()
-        .map(|((from, to), blocks)| GroupedMatches { from_file: from, to_file: to, blocks })
+        .map(|((from, to), blocks)| {
+            let from_content = from_files
+                .iter()
+                .find(|(f, _)| *f == from)
+                .map(|(_, c)| c.clone())
+                .unwrap_or_default();
+            GroupedMatches { from_file: from, to_file: to, blocks, from_content }
+        })
         .collect()
 }
 
@@ -1,84 +1,222 @@
-use std::fs;
 use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
 
 use atty::Stream;
+use similar::ChangeTag;
 
 use crate::formatters::traits::{DiffFormatter};
-use crate::formatters::diff::{DiffCliFormatter, DiffMarkdownFormatter};
-
+use crate::formatters::diff::{
+    side_by_side::terminal_width, DiffCliFormatter, DiffHtmlFormatter, DiffJsonFormatter, DiffMarkdownFormatter,
+    DiffSemanticFormatter, DiffSideBySideFormatter, DiffStatFormatter, DiffVerifyFormatter,
+};
+use crate::error::{Error, Result};
+use crate::types::{DiffReport, FileDiff, FileStatus, SourceFile};
+
+use super::codex::load_codex_side;
+use super::git::{load_rev_side, load_worktree_side, RevRange};
 use super::expand::expand_paths;
-use super::matcher::find_block_matches_multi;
+use super::options::{DiffFormat, DiffOptions};
+use super::policy::{ChangeTally, LineChanges};
+use super::pairing::pair_files;
+use super::renames::detect_renames;
+use super::patch::unified_patch;
+use super::matcher::find_block_matches_settled;
+use super::normalize::MatchMode;
+use super::inline::{diff_lines, Emphasis};
 use super::crossfile::group_and_filter_matches;
-use super::inline::diff_file_contents;
-
-/// Main diff orchestrator.
-pub fn run_diff(from: Vec<String>, to: Vec<String>, align_tags: bool) {
-    let from_files = expand_paths(&from);
-    let to_files   = expand_paths(&to);
-
-    let from_set: HashSet<_> = from_files.iter().collect();
-    let to_set: HashSet<_> = to_files.iter().collect();
-
-    // Deleted files
-    for f in from_set.difference(&to_set) {
-        println!("- {}", f);
+use super::tui;
+
+/// Lines each paired file keeps in place (every line of an unchanged file,
+/// the equal lines of a modified or renamed one), per file of each side.
+/// They stay with their own pair and are never offered to move detection.
+fn settled_lines(
+    files: &[FileDiff],
+    from_files: &[SourceFile],
+    to_files: &[SourceFile],
+    options: &DiffOptions,
+) -> (Vec<Vec<bool>>, Vec<Vec<bool>>) {
+    let blank = |side: &[SourceFile]| -> Vec<Vec<bool>> {
+        side.iter().map(|f| vec![false; f.content.lines().count()]).collect()
+    };
+    let (mut settled_from, mut settled_to) = (blank(from_files), blank(to_files));
+    let index = |side: &[SourceFile], path: &str| side.iter().position(|f| f.path == path);
+
+    for d in files.iter().filter(|d| d.status != FileStatus::Copied) {
+        let (Some(f), Some(t)) = (&d.from, &d.to) else {
+            continue;
+        };
+        let (Some(fi), Some(ti)) = (index(from_files, &f.path), index(to_files, &t.path)) else {
+            continue;
+        };
+        for line in diff_lines(&f.content, &t.content, &options.hunks, Emphasis::None) {
+            if let (ChangeTag::Equal, Some(i), Some(j)) = (line.tag, line.old_index, line.new_index) {
+                settled_from[fi][i] = true;
+                settled_to[ti][j] = true;
+            }
+        }
     }
+    (settled_from, settled_to)
+}
 
-    // New files
-    for f in to_set.difference(&from_set) {
-        println!("+ {}", f);
+/// Pair two loaded sides, detect renames/copies and run cross-file move
+/// detection over them. `by_position` pairs two lone files whatever their
+/// names (both sides were single file arguments).
+pub fn diff_sides(
+    from_files: &[SourceFile],
+    to_files: &[SourceFile],
+    by_position: bool,
+    options: &DiffOptions,
+) -> DiffReport {
+    let mut files = pair_files(from_files, to_files, by_position);
+
+    if let Some(threshold) = options.rename_threshold {
+        files = detect_renames(files, threshold, options.find_copies);
     }
 
-    // Same-file diffs
-    for f in from_set.intersection(&to_set) {
-        let from_content = fs::read_to_string(f).unwrap_or_default();
-        let to_content = fs::read_to_string(f).unwrap_or_default();
-
-        if from_content != to_content {
-            diff_file_contents(f, &from_content, &to_content);
-        }
-    }
+    let (settled_from, settled_to) = settled_lines(&files, from_files, to_files, options);
 
     // Cross-file semantic block diffs
     let from_pairs: Vec<_> = from_files
         .iter()
-        .map(|f| (f.clone(), fs::read_to_string(f).unwrap_or_default()))
+        .map(|f| (f.path.clone(), f.content.clone()))
         .collect();
 
     let to_pairs: Vec<_> = to_files
         .iter()
-        .map(|f| (f.clone(), fs::read_to_string(f).unwrap_or_default()))
+        .map(|f| (f.path.clone(), f.content.clone()))
         .collect();
 
-    let block_matches = find_block_matches_multi(&from_pairs, &to_pairs);
+    // Blocks that stayed inside their own paired file are covered by the
+    // inline diff; only report blocks that crossed files.
+    let same_file: HashSet<(&str, &str)> = files
+        .iter()
+        .filter_map(|d: &FileDiff| match (&d.from, &d.to) {
+            (Some(f), Some(t)) => Some((f.path.as_str(), t.path.as_str())),
+            _ => None,
+        })
+        .collect();
 
-    if block_matches.is_empty() {
-        return;
-    }
+    // reformatted lines count as moved only inside a block, so verification
+    // matches blocks across whitespace changes
+    let mode = match options.move_match {
+        MatchMode::Exact if options.verify_move_only => MatchMode::Whitespace,
+        mode => mode,
+    };
+    let block_matches: Vec<_> = find_block_matches_settled(&from_pairs, &to_pairs, mode, &settled_from, &settled_to)
+        .into_iter()
+        .filter(|m| !same_file.contains(&(m.from_file.as_str(), m.to_file.as_str())))
+        .collect();
 
-    let grouped = group_and_filter_matches(block_matches, &from_pairs);
+    let moves = if block_matches.is_empty() {
+        Vec::new()
+    } else {
+        group_and_filter_matches(block_matches, &from_pairs)
+    };
 
-    if grouped.is_empty() {
-        return;
+    DiffReport { files, moves, hunks: options.hunks }
+}
+
+/// Load both sides of `options` (from disk, git objects or two codices) and
+/// diff them.
+pub fn build_report(options: &DiffOptions) -> Result<DiffReport> {
+    let (from_files, to_files, by_position) = if let Some(spec) = &options.rev {
+        let range = RevRange::parse(spec)?;
+        let from = load_rev_side(&range.from, &options.from)?;
+        let to = match &range.to {
+            Some(rev) => load_rev_side(rev, &options.from)?,
+            None => load_worktree_side(&options.from)?,
+        };
+        (from, to, false)
+    } else if options.codex {
+        let [from, to] = [&options.from, &options.to].map(|side| match side.as_slice() {
+            [one] => Ok(one.as_str()),
+            _ => Err(Error::InvalidOption("--codex takes exactly two codex files".into())),
+        });
+        (load_codex_side(from?)?, load_codex_side(to?)?, false)
+    } else {
+        let from = expand_paths(&options.from, options.gitignore)?;
+        let to = expand_paths(&options.to, options.gitignore)?;
+        let by_position = from.single_file && to.single_file;
+        (from.files, to.files, by_position)
+    };
+
+    let select = |files: Vec<SourceFile>| -> Vec<SourceFile> {
+        files.into_iter().filter(|f| options.selection.accepts(&f.rel)).collect()
+    };
+    Ok(diff_sides(&select(from_files), &select(to_files), by_position, options))
+}
+
+/// Main diff orchestrator. Returns true when the run should exit 1: the
+/// sides differ or, with `fail_on`, one of those policies is violated, or
+/// with `verify_move_only`, some change is not a move.
+pub fn run_diff(options: &DiffOptions) -> Result<bool> {
+    let report = build_report(options)?;
+    if options.tui {
+        tui::run_tui(&report)?;
+    } else if !options.quiet {
+        render_report(&report, options)?;
     }
 
-    // Choose formatter
-    let use_md = to.iter().any(|f| f.ends_with(".md"));
-    let mut out: Box<dyn std::io::Write> = Box::new(std::io::stdout());
+    let changes = LineChanges::of(&report);
+    if options.verify_move_only {
+        return Ok(!changes.is_move_only());
+    }
 
-    if use_md {
-        let fmt = DiffMarkdownFormatter;
-        fmt.print_preamble(&mut *out);
-        fmt.print_index(&grouped, &mut *out);
-        fmt.print_contents(&grouped, &mut *out);
-    } else {
-        let fmt = DiffCliFormatter {
-            colored: atty::is(Stream::Stdout),
-            align_tags,
-        };
-        fmt.print_preamble(&mut *out);
-        fmt.print_index(&grouped, &mut *out);
-        fmt.print_contents(&grouped, &mut *out);
+    let tally = ChangeTally::from(&changes);
+    if options.fail_on.is_empty() {
+        return Ok(tally.has_differences());
     }
+
+    let violations = tally.violations(&options.fail_on);
+    if !options.quiet {
+        for (policy, lines) in &violations {
+            let noun = if *lines == 1 { "line" } else { "lines" };
+            eprintln!("❌ --fail-on {}: {} {} {}", policy.as_str(), lines, noun, policy.as_str());
+        }
+    }
+    Ok(!violations.is_empty())
+}
+
+/// Render `report` in the chosen format to `--out` or stdout.
+fn render_report(report: &DiffReport, options: &DiffOptions) -> Result<()> {
+    let mut out = Vec::new();
+    // colour only when a terminal will show it
+    let colored = options.out.is_none() && atty::is(Stream::Stdout);
+
+    let fmt: Box<dyn DiffFormatter> = match options.format {
+        DiffFormat::Patch => {
+            for d in &report.files {
+                write!(out, "{}", unified_patch(d, &options.hunks)).unwrap();
+            }
+            return write_output(options, &out);
+        }
+        DiffFormat::Json => Box::new(DiffJsonFormatter),
+        DiffFormat::Html => Box::new(DiffHtmlFormatter { emphasis: options.emphasis }),
+        DiffFormat::Md => Box::new(DiffMarkdownFormatter),
+        DiffFormat::Cli if options.verify_move_only => Box::new(DiffVerifyFormatter { colored }),
+        DiffFormat::Cli if options.semantic => Box::new(DiffSemanticFormatter { colored }),
+        DiffFormat::Cli if options.stat => Box::new(DiffStatFormatter { colored }),
+        DiffFormat::Cli if options.side_by_side => Box::new(DiffSideBySideFormatter {
+            colored,
+            emphasis: options.emphasis,
+            width: terminal_width(),
+        }),
+        DiffFormat::Cli => Box::new(DiffCliFormatter {
+            colored,
+            emphasis: options.emphasis,
+            align_tags: options.align_tags,
+        }),
+    };
+
+    fmt.render(report, &mut out);
+    write_output(options, &out)
 }
 
+/// Send a rendered report to `--out` or stdout.
+fn write_output(options: &DiffOptions, bytes: &[u8]) -> Result<()> {
+    match &options.out {
+        Some(path) => fs::write(path, bytes).map_err(|e| Error::io(path, e)),
+        None => std::io::stdout().write_all(bytes).map_err(|e| Error::io("<stdout>", e)),
+    }
+}
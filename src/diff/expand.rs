@@ -1,28 +1,115 @@
+use std::fs;
+use std::io;
 use std::path::Path;
 
-/// Expand a set of paths (files or directories) into a flat list of file paths.
-pub fn expand_paths(paths: &[String]) -> Vec<String> {
+use crate::error::{Error, Result};
+use crate::scanner::gitignore::GitIgnore;
+use crate::store::objects::STORE_DIR;
+use crate::types::SourceFile;
+
+/// Read one file into a [`SourceFile`] keyed by `rel`.
+fn load_file(path: &Path, rel: String) -> SourceFile {
+    SourceFile {
+        rel,
+        path: path.to_string_lossy().to_string(),
+        content: fs::read_to_string(path).unwrap_or_default(),
+    }
+}
+
+/// One side of a diff as given on the command line, loaded.
+#[derive(Debug, Clone, Default)]
+pub struct Expanded {
+    pub files: Vec<SourceFile>,
+    /// The side was a single file argument, not a directory or several paths
+    pub single_file: bool,
+}
+
+/// Expand a set of paths (files or directories) into loaded files.
+///
+/// Each file is keyed by its path relative to the argument it came from:
+/// `src/` yields `a.py`, `pkg/b.py`, …; a file argument is keyed by its name.
+/// This is what lets `ygg diff src/ -- old_src/` pair `src/a.py` with
+/// `old_src/a.py`.
+///
+/// Directory walks never enter `.git` or the `.ygg` store and, with
+/// `gitignore`, skip what the `.gitignore` files in effect ignore (those
+/// inside the directory and those above it up to the enclosing work tree).
+/// Files named explicitly are always loaded; a path that does not exist is
+/// an error.
+pub fn expand_paths(paths: &[String], gitignore: bool) -> Result<Expanded> {
     let mut files = Vec::new();
+    let single_file = matches!(paths, [one] if Path::new(one).is_file());
 
     for p in paths {
         let path = Path::new(p);
 
         if path.is_file() {
-            files.push(p.clone());
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| p.clone());
+            files.push(load_file(path, name));
         } else if path.is_dir() {
-            let walker = walkdir::WalkDir::new(path).into_iter();
-            for entry in walker.filter_map(Result::ok) {
-                if entry.file_type().is_file() {
-                    if let Some(s) = entry.path().to_str() {
-                        files.push(s.to_string());
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            let mut ignore = if gitignore { GitIgnore::for_root(path) } else { GitIgnore::default() };
+
+            let mut walker = walkdir::WalkDir::new(path).sort_by_file_name().into_iter();
+            while let Some(entry) = walker.next() {
+                let Ok(entry) = entry else {
+                    continue;
+                };
+                let rel = entry.path().strip_prefix(path).unwrap_or(entry.path());
+                let is_dir = entry.file_type().is_dir();
+
+                let skip = entry.depth() > 0
+                    && (entry.file_name() == ".git"
+                        || (is_dir && entry.file_name() == STORE_DIR)
+                        || ignore.is_ignored(&canonical.join(rel), is_dir));
+                if skip {
+                    if is_dir {
+                        walker.skip_current_dir();
                     }
+                    continue;
+                }
+
+                if is_dir && gitignore {
+                    ignore.add_dir(&canonical.join(rel));
+                } else if entry.file_type().is_file() {
+                    files.push(load_file(entry.path(), rel.to_string_lossy().to_string()));
                 }
             }
         } else {
-            eprintln!("⚠️ Path not found: {}", p);
+            return Err(Error::io(p, io::Error::new(io::ErrorKind::NotFound, "path not found")));
         }
     }
 
-    files
+    Ok(Expanded { files, single_file })
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_keys_files_relative_to_their_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("src");
+        fs::create_dir_all(root.join("pkg")).unwrap();
+        fs::write(root.join("a.py"), "a\n").unwrap();
+        fs::write(root.join("pkg/b.py"), "b\n").unwrap();
+
+        let Expanded { files, single_file } = expand_paths(&[root.to_string_lossy().to_string()], true).unwrap();
+        assert!(!single_file);
+        let rels: Vec<&str> = files.iter().map(|f| f.rel.as_str()).collect();
+        assert_eq!(rels, vec!["a.py", "pkg/b.py"]);
+        assert_eq!(files[1].content, "b\n");
+
+        let single = expand_paths(&[root.join("a.py").to_string_lossy().to_string()], true).unwrap();
+        assert!(single.single_file);
+        assert_eq!(single.files[0].rel, "a.py");
+
+        let missing = expand_paths(&[root.join("nope.py").to_string_lossy().to_string()], true);
+        assert!(matches!(missing, Err(Error::Io { .. })));
+    }
+}
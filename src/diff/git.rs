@@ -0,0 +1,168 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::error::{Error, Result};
+use crate::store::objects::STORE_DIR;
+use crate::types::SourceFile;
+
+/// `--rev A` compares ref A with the working tree; `--rev A..B` compares two refs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevRange {
+    pub from: String,
+    pub to: Option<String>,
+}
+
+impl RevRange {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = || Error::InvalidOption(format!("invalid --rev '{}' (expected A or A..B)", spec));
+
+        match spec.split_once("..") {
+            None if !spec.is_empty() => Ok(Self { from: spec.into(), to: None }),
+            Some((a, b)) if !a.is_empty() && !b.is_empty() && !b.starts_with('.') => {
+                Ok(Self { from: a.into(), to: Some(b.into()) })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Run git in the current directory and return stdout.
+fn git(args: &[&str], stdin: Option<&[u8]>) -> Result<Vec<u8>> {
+    let mut child = Command::new("git")
+        .args(args)
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::Git(format!("could not run git: {}", e)))?;
+
+    if let Some(input) = stdin {
+        // written from a separate thread so large outputs cannot deadlock the pipe
+        let mut pipe = child.stdin.take().expect("stdin is piped");
+        let input = input.to_vec();
+        std::thread::spawn(move || pipe.write_all(&input));
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| Error::Git(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Git(format!("git {}: {}", args[0], stderr.trim())));
+    }
+
+    Ok(output.stdout)
+}
+
+/// NUL-separated git paths, minus anything inside a snapshot store, which
+/// is never diffed (see `expand_paths`).
+fn split_nul(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .split(|&b| b == 0)
+        .filter(|p| !p.is_empty())
+        .map(|p| String::from_utf8_lossy(p).to_string())
+        .filter(|p| !p.split('/').any(|c| c == STORE_DIR))
+        .collect()
+}
+
+/// Parse `git cat-file --batch` output into one body per request, in order.
+/// Missing objects yield `None`.
+fn parse_batch(mut out: &[u8], count: usize) -> Vec<Option<Vec<u8>>> {
+    let mut bodies = Vec::with_capacity(count);
+
+    while bodies.len() < count {
+        let Some(nl) = out.iter().position(|&b| b == b'\n') else { break };
+        let header = String::from_utf8_lossy(&out[..nl]).to_string();
+        out = &out[nl + 1..];
+
+        let size = header
+            .split(' ')
+            .nth(2)
+            .filter(|_| !header.ends_with(" missing"))
+            .and_then(|s| s.parse::<usize>().ok());
+
+        match size {
+            Some(size) if out.len() > size => {
+                bodies.push(Some(out[..size].to_vec()));
+                out = &out[size + 1..];
+            }
+            _ => bodies.push(None),
+        }
+    }
+
+    bodies
+}
+
+/// Files of `rev` under `paths` (cwd-relative, like `git diff`), read from
+/// the object store. Paths are shown as `rev:path`.
+pub fn load_rev_side(rev: &str, paths: &[String]) -> Result<Vec<SourceFile>> {
+    let mut args = vec!["ls-tree", "-r", "-z", "--name-only", rev, "--"];
+    args.extend(paths.iter().map(String::as_str));
+    let names = split_nul(&git(&args, None)?);
+
+    let request: String = names.iter().map(|n| format!("{}:./{}\n", rev, n)).collect();
+    let bodies = parse_batch(&git(&["cat-file", "--batch"], Some(request.as_bytes()))?, names.len());
+
+    Ok(names
+        .into_iter()
+        .zip(bodies)
+        .filter_map(|(name, body)| {
+            let content = String::from_utf8(body?).unwrap_or_default();
+            Some(SourceFile { path: format!("{}:{}", rev, name), rel: name, content })
+        })
+        .collect())
+}
+
+/// Tracked and untracked-but-not-ignored files under `paths`, read from disk.
+pub fn load_worktree_side(paths: &[String]) -> Result<Vec<SourceFile>> {
+    let mut args = vec!["ls-files", "-z", "--cached", "--others", "--exclude-standard", "--"];
+    args.extend(paths.iter().map(String::as_str));
+
+    let mut names = split_nul(&git(&args, None)?);
+    names.dedup();
+
+    Ok(names
+        .into_iter()
+        .filter_map(|name| {
+            // deleted but still in the index
+            let content = std::fs::read(&name).ok()?;
+            Some(SourceFile {
+                path: name.clone(),
+                rel: name,
+                content: String::from_utf8(content).unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rev_range() {
+        assert_eq!(RevRange::parse("HEAD~2").unwrap(), RevRange { from: "HEAD~2".into(), to: None });
+        assert_eq!(
+            RevRange::parse("v1.0..main").unwrap(),
+            RevRange { from: "v1.0".into(), to: Some("main".into()) }
+        );
+        assert!(RevRange::parse("..main").is_err());
+        assert!(RevRange::parse("a...b").is_err());
+        assert!(RevRange::parse("").is_err());
+    }
+
+    #[test]
+    fn test_store_paths_are_skipped() {
+        let names = split_nul(b"a.rs\0.ygg/objects/ab\0sub/.ygg/index\0sub/b.rs\0");
+        assert_eq!(names, vec!["a.rs".to_string(), "sub/b.rs".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_cat_file_batch() {
+        let out = b"abc blob 3\nfoo\nHEAD:./x missing\nabd blob 0\n\n";
+        let bodies = parse_batch(out, 3);
+        assert_eq!(bodies, vec![Some(b"foo".to_vec()), None, Some(Vec::new())]);
+    }
+}
@@ -0,0 +1,174 @@
+use similar::ChangeTag;
+
+use crate::types::{Hunk, HunkLine};
+use super::inline::{diff_lines, Emphasis, InlineLine};
+use super::options::HunkOptions;
+
+/// A contiguous change with its context, lines carrying their emphasis
+/// (1-based line numbers, as in `@@`).
+#[derive(Debug, Clone)]
+pub struct LineHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<InlineLine>,
+}
+
+impl LineHunk {
+    /// `@@ -a,b +c,d @@`
+    pub fn header(&self) -> String {
+        format!("@@ -{},{} +{},{} @@", self.old_start, self.old_lines, self.new_start, self.new_lines)
+    }
+}
+
+/// Whether a change line starts a hunk; blank-line changes do not under
+/// `--ignore-blank-lines` (they are still shown inside other hunks).
+fn is_significant(line: &InlineLine, options: &HunkOptions) -> bool {
+    line.tag != ChangeTag::Equal && !(options.ignore_blank_lines && line.text().trim().is_empty())
+}
+
+/// Cut a full line diff into hunks with `options.context` lines around each
+/// significant change; hunks whose context overlaps are merged.
+pub fn group_hunks(lines: Vec<InlineLine>, options: &HunkOptions) -> Vec<LineHunk> {
+    let mut keep = vec![false; lines.len()];
+    for (i, line) in lines.iter().enumerate() {
+        if is_significant(line, options) {
+            let start = i.saturating_sub(options.context);
+            let end = (i + options.context + 1).min(lines.len());
+            keep[start..end].iter_mut().for_each(|k| *k = true);
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let (mut old_seen, mut new_seen) = (0, 0);
+    let mut current: Option<LineHunk> = None;
+
+    for (line, keep) in lines.into_iter().zip(keep) {
+        let (has_old, has_new) = (line.old_index.is_some(), line.new_index.is_some());
+
+        if keep {
+            let hunk = current.get_or_insert_with(|| LineHunk {
+                old_start: old_seen,
+                old_lines: 0,
+                new_start: new_seen,
+                new_lines: 0,
+                lines: Vec::new(),
+            });
+            hunk.old_lines += has_old as usize;
+            hunk.new_lines += has_new as usize;
+            hunk.lines.push(line);
+        } else if let Some(hunk) = current.take() {
+            hunks.push(hunk);
+        }
+
+        old_seen += has_old as usize;
+        new_seen += has_new as usize;
+    }
+    hunks.extend(current);
+
+    // like `@@`, an empty range reports the line before it
+    for h in &mut hunks {
+        h.old_start += (h.old_lines > 0) as usize;
+        h.new_start += (h.new_lines > 0) as usize;
+    }
+
+    hunks
+}
+
+/// Hunks of `from` → `to` with intra-line emphasis.
+pub fn line_hunks(from: &str, to: &str, options: &HunkOptions, emphasis: Emphasis) -> Vec<LineHunk> {
+    group_hunks(diff_lines(from, to, options, emphasis), options)
+}
+
+/// Plain hunks (no emphasis) for structured output.
+pub fn compute_hunks(from: &str, to: &str, options: &HunkOptions) -> Vec<Hunk> {
+    line_hunks(from, to, options, Emphasis::None)
+        .into_iter()
+        .map(|h| Hunk {
+            old_start: h.old_start,
+            old_lines: h.old_lines,
+            new_start: h.new_start,
+            new_lines: h.new_lines,
+            lines: h
+                .lines
+                .iter()
+                .map(|l| HunkLine {
+                    op: match l.tag {
+                        ChangeTag::Delete => '-',
+                        ChangeTag::Insert => '+',
+                        ChangeTag::Equal => ' ',
+                    },
+                    text: l.text(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::options::DiffAlgorithm;
+
+    fn context(n: usize) -> HunkOptions {
+        HunkOptions { context: n, ..HunkOptions::default() }
+    }
+
+    #[test]
+    fn test_hunks_match_unified_ranges() {
+        let hunks = compute_hunks("1\n2\n3\n4\n5\n6\n7\n8\n", "1\n2\n3\n4\n5\n6\n7\nEIGHT\n", &context(1));
+        assert_eq!(hunks.len(), 1);
+        let h = &hunks[0];
+        assert_eq!((h.old_start, h.old_lines, h.new_start, h.new_lines), (7, 2, 7, 2));
+        let ops: String = h.lines.iter().map(|l| l.op).collect();
+        assert_eq!(ops, " -+");
+        assert_eq!(h.lines[2].text, "EIGHT");
+
+        let added = compute_hunks("", "x\n", &context(3));
+        assert_eq!((added[0].old_start, added[0].old_lines, added[0].new_start), (0, 0, 1));
+    }
+
+    #[test]
+    fn test_distant_edits_make_separate_hunks() {
+        let old: String = (1..=40).map(|i| format!("line {}\n", i)).collect();
+        let new = old.replace("line 5\n", "five\n").replace("line 35\n", "thirty-five\n");
+        let hunks = compute_hunks(&old, &new, &context(2));
+        assert_eq!(hunks.len(), 2);
+        assert_eq!((hunks[0].old_start, hunks[0].old_lines), (3, 5));
+        assert_eq!((hunks[1].new_start, hunks[1].new_lines), (33, 5));
+
+        // overlapping context merges them
+        assert_eq!(compute_hunks(&old, &new, &context(15)).len(), 1);
+    }
+
+    #[test]
+    fn test_ignore_options() {
+        let old = "def f():\n    return X\n";
+        let new = "def f():\n        return x\n\n";
+
+        assert_eq!(compute_hunks(old, new, &HunkOptions::default()).len(), 1);
+
+        let ws = HunkOptions { ignore_whitespace: true, ..HunkOptions::default() };
+        assert_eq!(compute_hunks(old, new, &ws).len(), 1);
+
+        let all = HunkOptions {
+            ignore_whitespace: true,
+            ignore_case: true,
+            ignore_blank_lines: true,
+            ..HunkOptions::default()
+        };
+        assert!(compute_hunks(old, new, &all).is_empty());
+    }
+
+    #[test]
+    fn test_algorithms_agree_on_simple_edit() {
+        for algorithm in [DiffAlgorithm::Myers, DiffAlgorithm::Patience, DiffAlgorithm::Lcs] {
+            let options = HunkOptions { algorithm, ..context(0) };
+            let hunks = compute_hunks("a\nb\nc\n", "a\nB\nc\n", &options);
+            assert_eq!(hunks.len(), 1, "{:?}", algorithm);
+            assert_eq!((hunks[0].old_start, hunks[0].old_lines), (2, 1));
+        }
+    }
+}
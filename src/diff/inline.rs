@@ -1,27 +1,258 @@
-use similar::{TextDiff, ChangeTag};
+use std::io::Write;
 
-/// Print a single-file unified inline diff.
-pub fn diff_file_contents(file: &str, from: &str, to: &str) {
-    println!("\n📄 Diff for {file}:\n");
+use colored::*;
+use similar::{capture_diff_slices, ChangeTag, DiffTag, TextDiff};
 
-    let diff = TextDiff::from_lines(from, to);
+use super::hunks::line_hunks;
+use super::normalize::hunk_key;
+use super::options::HunkOptions;
 
+/// How finely changes inside a modified line are emphasised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Emphasis {
+    /// Highlight changed words
+    #[default]
+    Word,
+    /// Highlight changed characters
+    Char,
+    /// Whole-line colouring only
+    None,
+}
+
+/// Below this similarity a replaced block is treated as rewritten: every
+/// word would be emphasised, which is just noise.
+const MIN_EMPHASIS_RATIO: f32 = 0.5;
+
+/// Pieces of one line, `true` where the piece changed.
+pub type Segments = Vec<(bool, String)>;
+
+/// One line of a line diff with its intra-line emphasis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineLine {
+    pub tag: ChangeTag,
+    /// 0-based line numbers on each side
+    pub old_index: Option<usize>,
+    pub new_index: Option<usize>,
+    /// Line text without its newline
+    pub segments: Segments,
+}
+
+impl InlineLine {
+    pub fn text(&self) -> String {
+        self.segments.iter().map(|(_, s)| s.as_str()).collect()
+    }
+}
+
+/// Diff `old` against `new` at `emphasis` granularity and split the result
+/// back into lines.
+fn emphasize(old: &str, new: &str, emphasis: Emphasis) -> (Vec<Segments>, Vec<Segments>) {
+    let diff = match emphasis {
+        Emphasis::Char => TextDiff::from_chars(old, new),
+        _ => TextDiff::from_words(old, new),
+    };
+    let plain = emphasis == Emphasis::None || shared_ratio(&diff, old, new) < MIN_EMPHASIS_RATIO;
+
+    let (mut left, mut right) = (vec![Vec::new()], vec![Vec::new()]);
     for change in diff.iter_all_changes() {
-        let sign = match change.tag() {
-            ChangeTag::Delete => "-",
-            ChangeTag::Insert => "+",
-            ChangeTag::Equal => " ",
-        };
+        let emphasised = !plain && change.tag() != ChangeTag::Equal;
+        if change.tag() != ChangeTag::Insert {
+            push_segment(&mut left, change.value(), emphasised);
+        }
+        if change.tag() != ChangeTag::Delete {
+            push_segment(&mut right, change.value(), emphasised);
+        }
+    }
+
+    (trim_lines(left), trim_lines(right))
+}
+
+/// Share of the longer side left unchanged, ignoring whitespace (which two
+/// unrelated lines always have in common).
+fn shared_ratio<'a>(diff: &TextDiff<'a, 'a, 'a, str>, old: &str, new: &str) -> f32 {
+    let visible = |s: &str| s.chars().filter(|c| !c.is_whitespace()).count();
+    let shared: usize = diff
+        .iter_all_changes()
+        .filter(|c| c.tag() == ChangeTag::Equal)
+        .map(|c| visible(c.value()))
+        .sum();
+    match visible(old).max(visible(new)) {
+        0 => 1.0,
+        longest => shared as f32 / longest as f32,
+    }
+}
+
+/// Append `text` to the last line, starting a new line after every `\n`;
+/// runs with the same emphasis are merged.
+fn push_segment(lines: &mut Vec<Segments>, text: &str, emphasised: bool) {
+    for (i, part) in text.split('\n').enumerate() {
+        if i > 0 {
+            lines.push(Vec::new());
+        }
+        let part = part.trim_end_matches('\r');
+        if part.is_empty() {
+            continue;
+        }
+        let line = lines.last_mut().unwrap();
+        match line.last_mut() {
+            Some((e, text)) if *e == emphasised => text.push_str(part),
+            _ => line.push((emphasised, part.to_string())),
+        }
+    }
+}
+
+/// Drop the empty line left after the final newline.
+fn trim_lines(mut lines: Vec<Segments>) -> Vec<Segments> {
+    if lines.len() > 1 && lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Every line of `from` → `to`, compared under `options`; replaced blocks
+/// carry intra-line emphasis. Lines that only compare equal (whitespace,
+/// case) are shown as they read on the new side.
+pub fn diff_lines(from: &str, to: &str, options: &HunkOptions, emphasis: Emphasis) -> Vec<InlineLine> {
+    let old: Vec<&str> = from.split_inclusive('\n').collect();
+    let new: Vec<&str> = to.split_inclusive('\n').collect();
+    let old_keys: Vec<_> = old.iter().map(|l| hunk_key(l, options)).collect();
+    let new_keys: Vec<_> = new.iter().map(|l| hunk_key(l, options)).collect();
+
+    let ops = capture_diff_slices(options.algorithm.to_similar(), &old_keys, &new_keys);
+    let mut lines = Vec::new();
 
-        let colored = match change.tag() {
-            ChangeTag::Delete => format!("\x1b[91m{}{}\x1b[0m", sign, change),
-            ChangeTag::Insert => format!("\x1b[92m{}{}\x1b[0m", sign, change),
-            ChangeTag::Equal => format!(" {}{}", sign, change),
-        };
+    let plain = |tag, old_index, new_index, text: &str| InlineLine {
+        tag,
+        old_index,
+        new_index,
+        segments: vec![(false, text.trim_end_matches(['\n', '\r']).to_string())],
+    };
 
-        print!("{}", colored);
+    for op in &ops {
+        let (o, n) = (op.old_range(), op.new_range());
+        match op.tag() {
+            DiffTag::Equal => lines.extend(
+                o.zip(n).map(|(i, j)| plain(ChangeTag::Equal, Some(i), Some(j), new[j])),
+            ),
+            DiffTag::Delete => lines.extend(o.map(|i| plain(ChangeTag::Delete, Some(i), None, old[i]))),
+            DiffTag::Insert => lines.extend(n.map(|j| plain(ChangeTag::Insert, None, Some(j), new[j]))),
+            DiffTag::Replace => {
+                let (left, right) = emphasize(&old[o.clone()].concat(), &new[n.clone()].concat(), emphasis);
+                lines.extend(o.zip(left).map(|(i, segments)| InlineLine {
+                    tag: ChangeTag::Delete,
+                    old_index: Some(i),
+                    new_index: None,
+                    segments,
+                }));
+                lines.extend(n.zip(right).map(|(j, segments)| InlineLine {
+                    tag: ChangeTag::Insert,
+                    old_index: None,
+                    new_index: Some(j),
+                    segments,
+                }));
+            }
+        }
     }
 
-    println!();
+    lines
 }
 
+/// Write the hunks of a single-file diff (ANSI colours only when `colored`;
+/// changed words/characters are emphasised in colour mode). Nothing is
+/// written when every change is ignored under `options`.
+pub fn diff_file_contents(
+    file: &str,
+    from: &str,
+    to: &str,
+    colored: bool,
+    emphasis: Emphasis,
+    options: &HunkOptions,
+    out: &mut dyn Write,
+) {
+    let hunks = line_hunks(from, to, options, emphasis);
+    if hunks.is_empty() {
+        return;
+    }
+
+    writeln!(out, "\n📄 Diff for {file}:\n").unwrap();
+
+    for hunk in hunks {
+        if colored {
+            writeln!(out, "{}", hunk.header().cyan()).unwrap();
+        } else {
+            writeln!(out, "{}", hunk.header()).unwrap();
+        }
+
+        for line in hunk.lines {
+            let (sign, text) = match line.tag {
+                ChangeTag::Delete => ("-", paint(&line.segments, colored, true)),
+                ChangeTag::Insert => ("+", paint(&line.segments, colored, false)),
+                ChangeTag::Equal => ("  ", line.text()),
+            };
+
+            match line.tag {
+                ChangeTag::Delete if colored => writeln!(out, "{}{}", sign.bright_red(), text),
+                ChangeTag::Insert if colored => writeln!(out, "{}{}", sign.bright_green(), text),
+                _ => writeln!(out, "{}{}", sign, text),
+            }
+            .unwrap();
+        }
+    }
+
+    writeln!(out).unwrap();
+}
+
+/// Removed lines red / added lines green, changed pieces reversed and bold.
+pub fn paint(segments: &[(bool, String)], colored: bool, removed: bool) -> String {
+    segments
+        .iter()
+        .map(|(emphasised, s)| match (colored, removed, emphasised) {
+            (false, ..) => s.clone(),
+            (true, true, false) => s.bright_red().to_string(),
+            (true, true, true) => s.white().on_red().bold().to_string(),
+            (true, false, false) => s.bright_green().to_string(),
+            (true, false, true) => s.white().on_green().bold().to_string(),
+        })
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed(line: &InlineLine) -> Vec<&str> {
+        line.segments.iter().filter(|(e, _)| *e).map(|(_, s)| s.as_str()).collect()
+    }
+
+    #[test]
+    fn test_word_emphasis_marks_changed_token() {
+        let lines = diff_lines("a = 1\nlet total = price * qty\nb = 2\n", "a = 1\nlet total = cost * qty\nb = 2\n", &HunkOptions::default(), Emphasis::Word);
+        let tags: Vec<_> = lines.iter().map(|l| l.tag).collect();
+        assert_eq!(tags, [ChangeTag::Equal, ChangeTag::Delete, ChangeTag::Insert, ChangeTag::Equal]);
+
+        assert_eq!(changed(&lines[1]), ["price"]);
+        assert_eq!(changed(&lines[2]), ["cost"]);
+        assert_eq!(lines[2].text(), "let total = cost * qty");
+        assert_eq!((lines[2].old_index, lines[2].new_index), (None, Some(1)));
+    }
+
+    #[test]
+    fn test_char_emphasis_and_rewrites() {
+        let lines = diff_lines("value = 10\n", "value = 18\n", &HunkOptions::default(), Emphasis::Char);
+        assert_eq!(changed(&lines[0]), ["0"]);
+        assert_eq!(changed(&lines[1]), ["8"]);
+
+        // nothing in common: whole-line colouring only
+        let lines = diff_lines("alpha beta\n", "gamma delta\n", &HunkOptions::default(), Emphasis::Word);
+        assert!(lines.iter().all(|l| changed(l).is_empty()));
+    }
+
+    #[test]
+    fn test_multi_line_replace_keeps_line_split() {
+        let lines = diff_lines("x = 1\ny = 2\n", "x = 3\ny = 2\nz = 9\n", &HunkOptions::default(), Emphasis::Word);
+        let texts: Vec<_> = lines.iter().map(|l| (l.tag, l.text())).collect();
+        assert!(texts.contains(&(ChangeTag::Delete, "x = 1".to_string())));
+        assert!(texts.contains(&(ChangeTag::Insert, "x = 3".to_string())));
+        assert!(texts.contains(&(ChangeTag::Insert, "z = 9".to_string())));
+    }
+}
@@ -1,90 +1,351 @@
-use std::collections::{HashMap, HashSet};
+//! Cross-file block matcher.
+//!
+//! Both sides are split once into line tables (keys, hashes, boundary masks)
+//! addressed by interned file ids. Each pass (5 → 3 → 1 lines) seeds on
+//! rolling hashes of `m`-line windows of the target side, skipping windows
+//! that cross a structural boundary and seeds so common they carry no signal.
+//! Candidate blocks are grown in parallel per source file, then claimed in a
+//! fixed order so results are deterministic.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use rayon::prelude::*;
 
 use crate::types::BlockMatch;
 use super::block_hash::hash_line;
-use super::structural::extend_structural_block;
-
-/// Index: hash -> all occurrences (file, line_no)
-type LineIndex = HashMap<u64, Vec<(String, usize)>>;
-
-/// Build inverted index for all lines in `to` set.
-fn build_index(files: &[(String, String)]) -> LineIndex {
-    let mut index = LineIndex::new();
-    for (file, content) in files {
-        for (i, line) in content.lines().enumerate() {
-            let h = hash_line(line);
-            index.entry(h).or_default().push((file.clone(), i));
+use super::normalize::{normalize_line, MatchMode};
+use super::structural::{extend_structural_block, Boundaries};
+
+/// Minimum block sizes, largest first.
+const PASSES: [usize; 3] = [5, 3, 1];
+
+/// Seeds occurring more often than this on the target side (boilerplate such
+/// as `return None`) are not used to start blocks; they can still be covered
+/// when a block is extended through them.
+const MAX_SEED_OCCURRENCES: usize = 64;
+
+/// Multiplier of the polynomial rolling hash over line hashes.
+const ROLL_BASE: u64 = 0x100000001b3;
+
+/// (file id, line number)
+type Loc = (u32, u32);
+
+/// A file split into lines, with comparison keys, their hashes and its
+/// language's boundary mask.
+struct Table<'a> {
+    name: &'a str,
+    lines: Vec<&'a str>,
+    keys: Vec<Cow<'a, str>>,
+    hashes: Vec<u64>,
+    mask: Vec<bool>,
+}
+
+impl<'a> Table<'a> {
+    /// `settled` lines are masked like boundaries: they never take part in
+    /// a block.
+    fn new(path: &'a str, content: &'a str, mode: MatchMode, settled: Option<&[bool]>) -> Self {
+        let lines: Vec<&str> = content.lines().collect();
+        let keys: Vec<Cow<str>> = lines.iter().map(|l| normalize_line(l, mode)).collect();
+        let hashes = keys.iter().map(|k| hash_line(k)).collect();
+        let mut mask = Boundaries::from_path(path).mask(&lines);
+        if let Some(settled) = settled {
+            mask.iter_mut().zip(settled).for_each(|(m, s)| *m |= *s);
+        }
+        Self { name: path, lines, keys, hashes, mask }
+    }
+
+    /// Rolling hash of every `m`-line window; `None` where the window
+    /// contains a boundary line (no block can span it).
+    fn windows(&self, m: usize) -> Vec<Option<u64>> {
+        let n = self.lines.len();
+        if n < m {
+            return Vec::new();
+        }
+
+        let top = ROLL_BASE.wrapping_pow(m as u32 - 1);
+        let mut out = Vec::with_capacity(n - m + 1);
+        let mut hash = 0u64;
+        let mut boundaries = 0usize;
+
+        for i in 0..n {
+            hash = hash.wrapping_mul(ROLL_BASE).wrapping_add(self.hashes[i]);
+            boundaries += self.mask[i] as usize;
+
+            if i + 1 >= m {
+                let start = i + 1 - m;
+                out.push((boundaries == 0).then_some(hash));
+
+                hash = hash.wrapping_sub(self.hashes[start].wrapping_mul(top));
+                boundaries -= self.mask[start] as usize;
+            }
+        }
+
+        out
+    }
+
+    /// Character-level similarity (0–100) of `range` against the block of
+    /// the same length in `other`; 100 only when the lines are identical.
+    fn block_similarity(&self, range: (usize, usize), other: &Table, other_start: usize) -> u8 {
+        let len = range.1 - range.0;
+        let a = &self.lines[range.0..range.1];
+        let b = &other.lines[other_start..other_start + len];
+        if a == b {
+            return 100;
+        }
+        let ratio = similar::TextDiff::from_chars(&a.join("\n"), &b.join("\n")).ratio();
+        ((ratio * 100.0) as u8).min(99)
+    }
+}
+
+/// Seed index over the target side for one window size.
+fn build_seed_index(tables: &[Table], m: usize) -> HashMap<u64, Vec<Loc>> {
+    let mut index: HashMap<u64, Vec<Loc>> = HashMap::new();
+
+    for (id, table) in tables.iter().enumerate() {
+        for (j, h) in table.windows(m).into_iter().enumerate() {
+            if let Some(h) = h {
+                index.entry(h).or_default().push((id as u32, j as u32));
+            }
         }
     }
+
+    index.retain(|_, locs| locs.len() <= MAX_SEED_OCCURRENCES);
     index
 }
 
-/// Find block matches for a specific min block size.
-fn find_block_matches_single(
-    from_files: &[(String, String)],
-    to_files: &[(String, String)],
-    min_block_size: usize,
-) -> Vec<BlockMatch> {
-    let index = build_index(to_files);
-    let mut matches = Vec::new();
-    let mut visited_from = HashSet::new();
-    let mut visited_to = HashSet::new();
+/// Lines already claimed by a match, per interned file id.
+struct Visited {
+    from: Vec<Vec<bool>>,
+    to: Vec<Vec<bool>>,
+}
+
+impl Visited {
+    fn new(from: &[Table], to: &[Table]) -> Self {
+        Self {
+            from: from.iter().map(|t| vec![false; t.lines.len()]).collect(),
+            to: to.iter().map(|t| vec![false; t.lines.len()]).collect(),
+        }
+    }
+}
+
+/// A grown block before it is claimed.
+struct Candidate {
+    from: usize,
+    from_range: (usize, usize),
+    to: usize,
+    to_range: (usize, usize),
+    similarity: u8,
+}
+
+/// Grow candidate blocks of at least `m` lines for one source file, against
+/// the claims made by earlier passes.
+fn candidates_for(
+    fid: usize,
+    from: &Table,
+    to_tables: &[Table],
+    index: &HashMap<u64, Vec<Loc>>,
+    visited: &Visited,
+    m: usize,
+    mode: MatchMode,
+) -> Vec<Candidate> {
+    let mut claimed = visited.from[fid].clone();
+    let mut found = Vec::new();
 
-    for (from_file, content) in from_files {
-        let from_lines: Vec<&str> = content.lines().collect();
+    for (i, h) in from.windows(m).into_iter().enumerate() {
+        if claimed[i] {
+            continue;
+        }
+        let Some(locs) = h.and_then(|h| index.get(&h)) else {
+            continue;
+        };
+
+        for &(tid, j) in locs {
+            let (tid, j) = (tid as usize, j as usize);
+            if visited.to[tid][j] {
+                continue;
+            }
+
+            let to = &to_tables[tid];
+            let (f1, f2, t1, t2) =
+                extend_structural_block(&from.keys, &to.keys, &from.mask, &to.mask, i, j);
 
-        for (i, line) in from_lines.iter().enumerate() {
-            if visited_from.contains(&(from_file.clone(), i)) {
+            if f2 - f1 < m {
                 continue;
             }
 
-            let h = hash_line(line);
-            if let Some(candidates) = index.get(&h) {
-                for (to_file, j) in candidates {
-                    if visited_to.contains(&(to_file.clone(), *j)) {
-                        continue;
-                    }
-
-                    let (_, to_content) =
-                        to_files.iter().find(|(f, _)| f == to_file).unwrap();
-                    let to_lines: Vec<&str> = to_content.lines().collect();
-
-                    let (f1, f2, t1, t2) =
-                        extend_structural_block(&from_lines, &to_lines, i, *j);
-
-                    if f2 - f1 >= min_block_size {
-                        for k in f1..f2 {
-                            visited_from.insert((from_file.clone(), k));
-                        }
-                        for k in t1..t2 {
-                            visited_to.insert((to_file.clone(), k));
-                        }
-
-                        matches.push(BlockMatch {
-                            from_file: from_file.clone(),
-                            from_range: (f1, f2),
-                            to_file: to_file.clone(),
-                            to_range: (t1, t2),
-                        });
-                    }
-                }
+            // A lone line equal only after masking identifiers is
+            // coincidence (`x = y`), not a move.
+            let similarity = from.block_similarity((f1, f2), to, t1);
+            if m == 1 && mode == MatchMode::Identifiers && similarity < 100 {
+                continue;
             }
+
+            claimed[f1..f2].iter_mut().for_each(|c| *c = true);
+            found.push(Candidate {
+                from: fid,
+                from_range: (f1, f2),
+                to: tid,
+                to_range: (t1, t2),
+                similarity,
+            });
         }
     }
 
-    matches
+    found
 }
 
-/// Multi-pass block matching: 5 lines → 3 → 1
+/// Multi-pass block matching: 5 lines → 3 → 1, comparing lines under `mode`.
 pub fn find_block_matches_multi(
     from_files: &[(String, String)],
     to_files: &[(String, String)],
+    mode: MatchMode,
+) -> Vec<BlockMatch> {
+    find_block_matches_settled(from_files, to_files, mode, &[], &[])
+}
+
+/// [`find_block_matches_multi`] with lines settled in advance: line `k` of
+/// `from_files[i]` is left out when `settled_from[i][k]` is set (likewise
+/// for `to`; missing entries settle nothing). Settled lines neither seed nor
+/// extend a block.
+pub fn find_block_matches_settled(
+    from_files: &[(String, String)],
+    to_files: &[(String, String)],
+    mode: MatchMode,
+    settled_from: &[Vec<bool>],
+    settled_to: &[Vec<bool>],
 ) -> Vec<BlockMatch> {
+    let from_tables: Vec<Table> = from_files
+        .par_iter()
+        .enumerate()
+        .map(|(i, (path, content))| Table::new(path, content, mode, settled_from.get(i).map(Vec::as_slice)))
+        .collect();
+    let to_tables: Vec<Table> = to_files
+        .par_iter()
+        .enumerate()
+        .map(|(i, (path, content))| Table::new(path, content, mode, settled_to.get(i).map(Vec::as_slice)))
+        .collect();
+
+    let mut visited = Visited::new(&from_tables, &to_tables);
     let mut all = Vec::new();
-    for &min_size in &[5, 3, 1] {
-        let pass = find_block_matches_single(from_files, to_files, min_size);
-        all.extend(pass);
+
+    for m in PASSES {
+        let index = build_seed_index(&to_tables, m);
+
+        let candidates: Vec<Vec<Candidate>> = from_tables
+            .par_iter()
+            .enumerate()
+            .map(|(fid, from)| candidates_for(fid, from, &to_tables, &index, &visited, m, mode))
+            .collect();
+
+        // Claim in source order; a target line is matched at most once.
+        for c in candidates.into_iter().flatten() {
+            let (t1, t2) = c.to_range;
+            if visited.to[c.to][t1..t2].iter().any(|&v| v) {
+                continue;
+            }
+            let (f1, f2) = c.from_range;
+            visited.from[c.from][f1..f2].iter_mut().for_each(|v| *v = true);
+            visited.to[c.to][t1..t2].iter_mut().for_each(|v| *v = true);
+
+            all.push(BlockMatch {
+                from_file: from_tables[c.from].name.to_string(),
+                from_range: c.from_range,
+                to_file: to_tables[c.to].name.to_string(),
+                to_range: c.to_range,
+                similarity: c.similarity,
+            });
+        }
     }
+
     all
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_reported_once_across_passes() {
+        let from = vec![("a.py".to_string(), "x = 1\ny = 2\nz = 3\n".to_string())];
+        let to = vec![("b.py".to_string(), "x = 1\ny = 2\nz = 3\n".to_string())];
+        let matches = find_block_matches_multi(&from, &to, MatchMode::Exact);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].from_range, (0, 3));
+    }
+
+    #[test]
+    fn test_rust_blocks_split_at_items() {
+        let src = "fn a() {\n    let x = 1;\n    let y = 2;\n}\nfn b() {\n    let z = 3;\n}\n";
+        let from = vec![("a.rs".to_string(), src.to_string())];
+        let to = vec![("b.rs".to_string(), src.to_string())];
+        let ranges: Vec<_> = find_block_matches_multi(&from, &to, MatchMode::Exact)
+            .into_iter()
+            .map(|m| m.from_range)
+            .collect();
+        // item lines are never matched, bodies are matched separately
+        assert!(ranges.contains(&(1, 4)));
+        assert!(ranges.contains(&(5, 7)));
+        assert!(!ranges.iter().any(|r| r.0 <= 4 && r.1 > 4));
+    }
+
+    #[test]
+    fn test_fuzzy_modes_score_edited_blocks() {
+        let old = "def total(items):\n    acc = 0\n    for item in items:\n        acc += item.price\n    return acc\n";
+        let new = "def total(entries):\n  acc = 0\n  for entry in entries:\n      acc += entry.price\n  return acc\n";
+        let from = vec![("a.py".to_string(), old.to_string())];
+        let to = vec![("b.py".to_string(), new.to_string())];
+
+        assert!(find_block_matches_multi(&from, &to, MatchMode::Exact).is_empty());
+
+        // re-indented lines match, the renamed ones still split the block
+        let ws: Vec<_> = find_block_matches_multi(&from, &to, MatchMode::Whitespace)
+            .into_iter()
+            .map(|m| m.from_range)
+            .collect();
+        assert_eq!(ws, vec![(1, 2), (4, 5)]);
+
+        let ids = find_block_matches_multi(&from, &to, MatchMode::Identifiers);
+        assert_eq!(ids.len(), 1);
+        assert_eq!(ids[0].from_range, (1, 5));
+        assert!(ids[0].similarity < 100);
+    }
+
+    #[test]
+    fn test_rolling_windows_skip_boundaries() {
+        let table = Table::new("a.py", "a = 1\nb = 2\n\nc = 3\nd = 4\n", MatchMode::Exact, None);
+        let windows = table.windows(2);
+        assert_eq!(windows.len(), 4);
+        assert!(windows[0].is_some());
+        assert!(windows[1].is_none() && windows[2].is_none());
+        assert!(windows[3].is_some());
+
+        // same content, same window hash regardless of position
+        let other = Table::new("b.py", "zzz\na = 1\nb = 2\n", MatchMode::Exact, None);
+        assert_eq!(other.windows(2)[1], windows[0]);
+    }
+
+    #[test]
+    fn test_settled_lines_are_never_matched() {
+        let from = vec![("a.py".to_string(), "x = 1\ny = 2\nz = 3\n".to_string())];
+        let to = vec![("b.py".to_string(), "x = 1\ny = 2\nz = 3\n".to_string())];
+        let matches = find_block_matches_settled(&from, &to, MatchMode::Exact, &[vec![false, true, false]], &[]);
+        let ranges: Vec<_> = matches.iter().map(|m| m.from_range).collect();
+        assert_eq!(ranges, vec![(0, 1), (2, 3)]);
+    }
+
+    #[test]
+    fn test_common_lines_do_not_seed_but_are_covered() {
+        // `return None` is everywhere; the moved block still includes it
+        let noise: String = (0..100).map(|k| format!("def f{}():\n    return None\n", k)).collect();
+        let moved = "def g():\n    value = compute()\n    return None\n";
+        let from = vec![("a.py".to_string(), moved.to_string())];
+        let to = vec![("b.py".to_string(), format!("{}{}", noise, moved))];
+
+        let matches = find_block_matches_multi(&from, &to, MatchMode::Exact);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].from_range, (1, 3));
+        assert_eq!(matches[0].to_range, (201, 203));
+    }
+}
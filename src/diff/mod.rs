@@ -1,10 +1,25 @@
 pub mod engine;
 pub mod expand;
+pub mod codex;
+pub mod git;
+pub mod pairing;
+pub mod renames;
+pub mod options;
+pub mod patch;
 pub mod matcher;
+pub mod normalize;
 pub mod structural;
 pub mod block_hash;
 pub mod crossfile;
 pub mod inline;
+pub mod hunks;
+pub mod policy;
+pub mod semantic;
+pub mod tui;
 
 pub use engine::run_diff;
+pub use inline::Emphasis;
+pub use normalize::MatchMode;
+pub use options::{DiffAlgorithm, DiffFormat, DiffOptions, HunkOptions};
+pub use policy::FailOn;
 
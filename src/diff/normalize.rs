@@ -0,0 +1,149 @@
+use std::borrow::Cow;
+
+use super::options::HunkOptions;
+
+/// How lines are compared when looking for moved blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum MatchMode {
+    /// Byte-for-byte equal lines
+    #[default]
+    Exact,
+    /// Ignore indentation and spacing between tokens (re-indented or
+    /// re-spaced code still matches; `not x` and `notx` do not)
+    Whitespace,
+    /// Ignore whitespace and treat every identifier as equal, so renamed
+    /// variables still match; common keywords are kept
+    Identifiers,
+}
+
+/// Keywords kept verbatim in identifier mode so control flow still has to agree.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "case", "catch", "class", "const", "continue", "def",
+    "default", "do", "elif", "else", "enum", "except", "export", "false", "False", "finally",
+    "fn", "for", "from", "func", "function", "if", "impl", "import", "in", "interface", "is",
+    "lambda", "let", "loop", "match", "mod", "mut", "new", "None", "not", "null", "or", "and",
+    "pass", "pub", "raise", "return", "self", "Self", "static", "struct", "switch", "this",
+    "throw", "trait", "true", "True", "try", "type", "use", "var", "while", "with", "yield",
+];
+
+fn strip_whitespace(line: &str) -> String {
+    line.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// `line` with whitespace normalized between tokens: dropped at the ends and
+/// next to punctuation, a single space where it separates two words.
+pub fn collapse_whitespace(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut gap = false;
+    for c in line.trim().chars() {
+        if c.is_whitespace() {
+            gap = true;
+            continue;
+        }
+        if gap && out.chars().next_back().is_some_and(is_word) && is_word(c) {
+            out.push(' ');
+        }
+        gap = false;
+        out.push(c);
+    }
+    out
+}
+
+fn mask_identifiers(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            let mut word = String::from(c);
+            while let Some(&n) = chars.peek() {
+                if n.is_alphanumeric() || n == '_' {
+                    word.push(n);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if KEYWORDS.contains(&word.as_str()) {
+                out.push_str(&word);
+            } else {
+                out.push('\u{1}');
+            }
+        } else if c.is_ascii_digit() {
+            // keep numbers whole so `x1` / `1` handling stays unambiguous
+            out.push(c);
+            while let Some(&n) = chars.peek() {
+                if n.is_alphanumeric() || n == '.' || n == '_' {
+                    out.push(n);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+
+    out
+}
+
+/// The comparison key of a line under `mode`.
+pub fn normalize_line(line: &str, mode: MatchMode) -> Cow<'_, str> {
+    match mode {
+        MatchMode::Exact => Cow::Borrowed(line),
+        MatchMode::Whitespace => Cow::Owned(collapse_whitespace(line)),
+        MatchMode::Identifiers => Cow::Owned(mask_identifiers(line)),
+    }
+}
+
+/// Key a line is compared by when building hunks (`--ignore-whitespace`,
+/// `--ignore-case`); the line itself when nothing is ignored.
+pub fn hunk_key<'a>(line: &'a str, options: &HunkOptions) -> Cow<'a, str> {
+    let mut key = Cow::Borrowed(line.trim_end_matches(['\n', '\r']));
+    if options.ignore_whitespace {
+        key = Cow::Owned(strip_whitespace(&key));
+    }
+    if options.ignore_case {
+        key = Cow::Owned(key.to_lowercase());
+    }
+    key
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whitespace_mode() {
+        assert_eq!(
+            normalize_line("    x = f(a, b)", MatchMode::Whitespace),
+            normalize_line("x=f(a,b)", MatchMode::Whitespace)
+        );
+        assert_eq!(normalize_line("  x ", MatchMode::Exact), "  x ");
+        assert_eq!(collapse_whitespace("  if  not x :"), "if not x:");
+        assert_ne!(collapse_whitespace("not x"), collapse_whitespace("notx"));
+    }
+
+    #[test]
+    fn test_identifier_mode() {
+        let a = normalize_line("    return total + item.price", MatchMode::Identifiers);
+        let b = normalize_line("return sum + entry.cost", MatchMode::Identifiers);
+        assert_eq!(a, b);
+
+        // keywords and literals still have to agree
+        assert_ne!(a, normalize_line("yield total + item.price", MatchMode::Identifiers));
+        assert_ne!(
+            normalize_line("x = 1", MatchMode::Identifiers),
+            normalize_line("x = 2", MatchMode::Identifiers)
+        );
+    }
+}
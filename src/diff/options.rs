@@ -0,0 +1,157 @@
+use super::inline::Emphasis;
+use super::normalize::MatchMode;
+use super::policy::FailOn;
+use crate::scanner::selection::Selection;
+
+/// How `ygg diff` renders its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum DiffFormat {
+    /// Inline terminal diff plus the cross-file report
+    #[default]
+    Cli,
+    /// Unified diff accepted by `git apply` / `patch -p1`
+    Patch,
+    /// Structured report (file status, hunks, cross-file moves)
+    Json,
+    /// Standalone HTML page with highlighted hunks and moves
+    Html,
+    /// Markdown report: summary, file table, ```diff hunks and moves
+    #[value(alias = "markdown")]
+    Md,
+}
+
+impl DiffFormat {
+    /// Format implied by an `--out` file name, if its extension names one.
+    pub fn from_path(path: &str) -> Option<Self> {
+        match std::path::Path::new(path).extension()?.to_str()? {
+            "md" | "markdown" => Some(DiffFormat::Md),
+            "html" | "htm" => Some(DiffFormat::Html),
+            "json" => Some(DiffFormat::Json),
+            "patch" | "diff" => Some(DiffFormat::Patch),
+            _ => None,
+        }
+    }
+}
+
+/// Line diff algorithm, as offered by `similar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum DiffAlgorithm {
+    /// Shortest edit script; the usual choice
+    #[default]
+    Myers,
+    /// Anchors on lines unique to both sides; keeps moved functions readable
+    Patience,
+    /// Longest common subsequence; slow on large files
+    Lcs,
+}
+
+impl DiffAlgorithm {
+    pub fn to_similar(self) -> similar::Algorithm {
+        match self {
+            DiffAlgorithm::Myers => similar::Algorithm::Myers,
+            DiffAlgorithm::Patience => similar::Algorithm::Patience,
+            DiffAlgorithm::Lcs => similar::Algorithm::Lcs,
+        }
+    }
+}
+
+/// How modified files are compared line by line and cut into hunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HunkOptions {
+    /// Unchanged lines around each hunk
+    pub context: usize,
+    pub algorithm: DiffAlgorithm,
+    /// Lines equal apart from whitespace count as unchanged
+    pub ignore_whitespace: bool,
+    /// Changes that only add or remove blank lines start no hunk
+    pub ignore_blank_lines: bool,
+    /// Lines equal apart from letter case count as unchanged
+    pub ignore_case: bool,
+}
+
+impl Default for HunkOptions {
+    fn default() -> Self {
+        Self {
+            context: 3,
+            algorithm: DiffAlgorithm::Myers,
+            ignore_whitespace: false,
+            ignore_blank_lines: false,
+            ignore_case: false,
+        }
+    }
+}
+
+/// Everything `run_diff` needs, resolved from the CLI.
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    /// Original files or directories
+    pub from: Vec<String>,
+    /// Modified files or directories
+    pub to: Vec<String>,
+    /// Treat `from` / `to` as one codex file each and diff their contents
+    pub codex: bool,
+    /// Git revision(s) to read instead of the filesystem (`A` or `A..B`);
+    /// `from` then limits the paths compared
+    pub rev: Option<String>,
+    /// `--show` / `--ignore` / `--only` rules, applied to the relative path
+    /// of every file on both sides
+    pub selection: Selection,
+    /// Skip files ignored by `.gitignore` when walking directories
+    pub gitignore: bool,
+    pub format: DiffFormat,
+    /// Write the report here instead of stdout (never coloured)
+    pub out: Option<String>,
+    /// Context, algorithm and what to ignore when building hunks
+    pub hunks: HunkOptions,
+    /// Render modified files as two columns instead of an inline dump
+    pub side_by_side: bool,
+    /// Print per-file insertion/deletion/move counts instead of the diff
+    pub stat: bool,
+    /// Granularity of the highlighting inside modified lines
+    pub emphasis: Emphasis,
+    pub align_tags: bool,
+    /// Minimum similarity (%) for rename/copy detection; `None` disables it
+    pub rename_threshold: Option<u8>,
+    /// Also report added files derived from a surviving file as copies
+    pub find_copies: bool,
+    /// Line comparison used for cross-file move detection
+    pub move_match: MatchMode,
+    /// Print nothing; only the exit status reports differences
+    pub quiet: bool,
+    /// Exit 1 only for these kinds of change instead of for any difference
+    pub fail_on: Vec<FailOn>,
+    /// Classify changed lines and exit 1 unless all were moved or reformatted
+    pub verify_move_only: bool,
+    /// Report changed items (functions, classes, types) instead of lines
+    pub semantic: bool,
+    /// Browse the diff in a full-screen terminal UI instead of printing it
+    pub tui: bool,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            from: Vec::new(),
+            to: Vec::new(),
+            codex: false,
+            rev: None,
+            selection: Selection::default(),
+            gitignore: true,
+            format: DiffFormat::Cli,
+            out: None,
+            hunks: HunkOptions::default(),
+            side_by_side: false,
+            stat: false,
+            emphasis: Emphasis::Word,
+            align_tags: false,
+            rename_threshold: Some(50),
+            find_copies: false,
+            move_match: MatchMode::Exact,
+            quiet: false,
+            fail_on: Vec::new(),
+            verify_move_only: false,
+            semantic: false,
+            tui: false,
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use crate::types::{FileDiff, FileStatus, SourceFile};
+
+/// Pair both sides of a diff by root-relative path and classify each file.
+///
+/// With `by_position` (each side was one file argument, `ygg diff new.py --
+/// old.py`) the two are paired regardless of name. Results are ordered by
+/// relative path.
+pub fn pair_files(from: &[SourceFile], to: &[SourceFile], by_position: bool) -> Vec<FileDiff> {
+    if by_position && from.len() == 1 && to.len() == 1 {
+        return vec![classify(Some(from[0].clone()), Some(to[0].clone()))];
+    }
+
+    let mut paired: BTreeMap<&str, (Option<&SourceFile>, Option<&SourceFile>)> = BTreeMap::new();
+
+    for f in from {
+        paired.entry(f.rel.as_str()).or_default().0 = Some(f);
+    }
+    for t in to {
+        paired.entry(t.rel.as_str()).or_default().1 = Some(t);
+    }
+
+    paired
+        .into_values()
+        .map(|(f, t)| classify(f.cloned(), t.cloned()))
+        .collect()
+}
+
+fn classify(from: Option<SourceFile>, to: Option<SourceFile>) -> FileDiff {
+    let status = match (&from, &to) {
+        (Some(_), None) => FileStatus::Removed,
+        (None, Some(_)) => FileStatus::Added,
+        (Some(f), Some(t)) if f.rel != t.rel => FileStatus::Renamed,
+        (Some(f), Some(t)) if f.content == t.content => FileStatus::Unchanged,
+        _ => FileStatus::Modified,
+    };
+    FileDiff { status, from, to, similarity: None }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(root: &str, rel: &str, content: &str) -> SourceFile {
+        SourceFile {
+            rel: rel.into(),
+            path: format!("{}/{}", root, rel),
+            content: content.into(),
+        }
+    }
+
+    #[test]
+    fn test_pairs_by_relative_path() {
+        let from = vec![
+            file("old", "a.py", "x\n"),
+            file("old", "b.py", "same\n"),
+            file("old", "gone.py", "bye\n"),
+        ];
+        let to = vec![
+            file("new", "a.py", "y\n"),
+            file("new", "b.py", "same\n"),
+            file("new", "fresh.py", "hi\n"),
+        ];
+
+        let diffs = pair_files(&from, &to, false);
+        let summary: Vec<(&str, FileStatus)> = diffs
+            .iter()
+            .map(|d| (d.rel(), d.status))
+            .collect();
+
+        assert_eq!(summary, vec![
+            ("a.py", FileStatus::Modified),
+            ("b.py", FileStatus::Unchanged),
+            ("fresh.py", FileStatus::Added),
+            ("gone.py", FileStatus::Removed),
+        ]);
+        assert_eq!(diffs[0].from.as_ref().unwrap().path, "old/a.py");
+        assert_eq!(diffs[0].to.as_ref().unwrap().path, "new/a.py");
+    }
+
+    #[test]
+    fn test_single_files_pair_regardless_of_name() {
+        let diffs = pair_files(
+            &[file(".", "controller.py", "a\n")],
+            &[file(".", "controller_old.py", "b\n")],
+            true,
+        );
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].status, FileStatus::Renamed);
+    }
+
+    #[test]
+    fn test_lone_files_found_in_directories_pair_by_path() {
+        let diffs = pair_files(&[file("old", "a.py", "a\n")], &[file("new", "b.py", "b\n")], false);
+        let statuses: Vec<FileStatus> = diffs.iter().map(|d| d.status).collect();
+        assert_eq!(statuses, vec![FileStatus::Removed, FileStatus::Added]);
+    }
+}
@@ -0,0 +1,117 @@
+use similar::TextDiff;
+
+use crate::types::{FileDiff, FileStatus};
+
+use super::options::HunkOptions;
+
+/// Render one paired file as a git-style unified patch.
+///
+/// Paths are written as `a/<rel>` / `b/<rel>`, so the result applies with
+/// `git apply` (or `patch -p1`) from inside the original root. Renames and
+/// copies get git's extended headers. Unchanged files yield "". Only the
+/// context and algorithm are honoured: ignoring whitespace, blank lines or
+/// case would produce a patch that does not apply.
+pub fn unified_patch(diff: &FileDiff, hunks: &HunkOptions) -> String {
+    if diff.status == FileStatus::Unchanged {
+        return String::new();
+    }
+
+    let old_rel = diff.from.as_ref().map(|f| f.rel.as_str());
+    let new_rel = diff.to.as_ref().map(|f| f.rel.as_str());
+    let old_text = diff.from.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+    let new_text = diff.to.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+
+    let (a, b) = match (old_rel, new_rel) {
+        (Some(a), Some(b)) => (a, b),
+        (Some(a), None) => (a, a),
+        (None, Some(b)) => (b, b),
+        (None, None) => return String::new(),
+    };
+
+    let mut out = format!("diff --git a/{} b/{}\n", a, b);
+
+    match diff.status {
+        FileStatus::Added => out.push_str("new file mode 100644\n"),
+        FileStatus::Removed => out.push_str("deleted file mode 100644\n"),
+        FileStatus::Renamed | FileStatus::Copied => {
+            let verb = if diff.status == FileStatus::Copied { "copy" } else { "rename" };
+            if let Some(sim) = diff.similarity {
+                out.push_str(&format!("similarity index {}%\n", sim));
+            }
+            out.push_str(&format!("{verb} from {}\n{verb} to {}\n", a, b));
+        }
+        _ => {}
+    }
+
+    // a pure rename has no hunks and must not carry ---/+++ lines
+    if old_text == new_text {
+        return out;
+    }
+
+    let old_header = match diff.status {
+        FileStatus::Added => "/dev/null".to_string(),
+        _ => format!("a/{}", a),
+    };
+    let new_header = match diff.status {
+        FileStatus::Removed => "/dev/null".to_string(),
+        _ => format!("b/{}", b),
+    };
+
+    let text_diff = TextDiff::configure()
+        .algorithm(hunks.algorithm.to_similar())
+        .diff_lines(old_text, new_text);
+    out.push_str(
+        &text_diff
+            .unified_diff()
+            .context_radius(hunks.context)
+            .header(&old_header, &new_header)
+            .to_string(),
+    );
+
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SourceFile;
+
+    fn side(rel: &str, content: &str) -> Option<SourceFile> {
+        Some(SourceFile { rel: rel.into(), path: rel.into(), content: content.into() })
+    }
+
+    #[test]
+    fn test_modified_file_hunks_and_missing_newline() {
+        let diff = FileDiff {
+            similarity: None,
+            status: FileStatus::Modified,
+            from: side("m.py", "a\nb\nc\nd\ne\nf\ng\n"),
+            to: side("m.py", "a\nb\nc\nd\ne\nf\nG"),
+        };
+        let patch = unified_patch(&diff, &HunkOptions { context: 1, ..HunkOptions::default() });
+        assert!(patch.starts_with("diff --git a/m.py b/m.py\n--- a/m.py\n+++ b/m.py\n"));
+        assert!(patch.contains("@@ -6,2 +6,2 @@\n f\n-g\n+G\n\\ No newline at end of file\n"));
+    }
+
+    #[test]
+    fn test_added_removed_and_renamed_headers() {
+        let added = FileDiff { status: FileStatus::Added, from: None, to: side("n.py", "x\n"), similarity: None };
+        let patch = unified_patch(&added, &HunkOptions::default());
+        assert!(patch.contains("new file mode 100644\n--- /dev/null\n+++ b/n.py\n@@ -0,0 +1 @@\n+x\n"));
+
+        let removed = FileDiff { status: FileStatus::Removed, from: side("o.py", "x\n"), to: None, similarity: None };
+        assert!(unified_patch(&removed, &HunkOptions::default()).contains("--- a/o.py\n+++ /dev/null\n"));
+
+        let renamed = FileDiff {
+            similarity: Some(100),
+            status: FileStatus::Renamed,
+            from: side("old.py", "x\n"),
+            to: side("new.py", "x\n"),
+        };
+        assert_eq!(
+            unified_patch(&renamed, &HunkOptions::default()),
+            "diff --git a/old.py b/new.py\nsimilarity index 100%\nrename from old.py\nrename to new.py\n"
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use similar::ChangeTag;
+
+use crate::types::{DiffReport, FileStatus};
+use super::inline::{diff_lines, Emphasis};
+use super::normalize::collapse_whitespace;
+
+/// A kind of change that `--fail-on` turns into exit status 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FailOn {
+    /// Any cross-file block move
+    Moved,
+    /// Non-blank lines added that were not moved from elsewhere
+    Added,
+    /// Non-blank lines removed that do not reappear elsewhere
+    Removed,
+}
+
+impl FailOn {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailOn::Moved => "moved",
+            FailOn::Added => "added",
+            FailOn::Removed => "removed",
+        }
+    }
+}
+
+/// What became of one changed (non-blank) line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineClass {
+    /// Part of a cross-file move block, with identical text
+    Moved,
+    /// Part of a cross-file move block, with the same tokens but different
+    /// whitespace
+    Reformatted,
+    /// New line with no counterpart
+    Added,
+    /// Deleted line with no counterpart
+    Removed,
+}
+
+impl LineClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineClass::Moved => "moved",
+            LineClass::Reformatted => "reformatted",
+            LineClass::Added => "added",
+            LineClass::Removed => "removed",
+        }
+    }
+}
+
+/// A changed line and its classification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassifiedLine {
+    pub class: LineClass,
+    /// `Delete` for a line of the old side, `Insert` for the new side
+    pub tag: ChangeTag,
+    pub path: String,
+    /// 1-based line number in `path`
+    pub line: usize,
+    pub text: String,
+}
+
+/// Every changed line of a report, each classified as moved, reformatted,
+/// added or removed.
+#[derive(Debug, Clone, Default)]
+pub struct LineChanges {
+    /// Files added, removed, renamed, copied or with a change not ignored
+    pub files: usize,
+    /// Old-side lines first, then new-side lines, each in file order
+    pub lines: Vec<ClassifiedLine>,
+}
+
+/// Pair two still unexplained lines as moved (same text) or reformatted
+/// (same tokens, see [`collapse_whitespace`]). Returns false, changing
+/// nothing, when they differ.
+fn pair(old: &mut ClassifiedLine, new: &mut ClassifiedLine) -> bool {
+    if old.class != LineClass::Removed || new.class != LineClass::Added {
+        return false;
+    }
+    let class = if old.text == new.text {
+        LineClass::Moved
+    } else if collapse_whitespace(&old.text) == collapse_whitespace(&new.text) {
+        LineClass::Reformatted
+    } else {
+        return false;
+    };
+    old.class = class;
+    new.class = class;
+    true
+}
+
+impl LineChanges {
+    /// Classify the changed lines of `report`, honouring its hunk options
+    /// (`-w` etc.) so ignored changes are not lines at all.
+    ///
+    /// Only cross-file move blocks explain a change: each line of a block is
+    /// paired with the line at the same offset of its target, and the block
+    /// is grown over equal changed lines right before and after it (item
+    /// headers such as `fn` / `def`, which the matcher never puts inside a
+    /// block). Anything else, including lines reordered within a file or
+    /// text that merely reappears somewhere, is added or removed.
+    pub fn of(report: &DiffReport) -> Self {
+        let mut files = 0;
+        let (mut old, mut new) = (Vec::new(), Vec::new());
+
+        for d in report.files.iter().filter(|d| d.status != FileStatus::Unchanged) {
+            let from = d.from.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+            let to = d.to.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+            let from_path = d.from.as_ref().map(|f| f.path.as_str()).unwrap_or("");
+            let to_path = d.to.as_ref().map(|f| f.path.as_str()).unwrap_or("");
+
+            let mut changed = d.status != FileStatus::Modified;
+            for line in diff_lines(from, to, &report.hunks, Emphasis::None) {
+                if line.tag == ChangeTag::Equal {
+                    continue;
+                }
+                let text = line.text();
+                let blank = text.trim().is_empty();
+                changed |= !(blank && report.hunks.ignore_blank_lines);
+                if blank {
+                    continue;
+                }
+                let (side, path, index) = match (line.old_index, line.new_index) {
+                    (Some(i), _) if line.tag == ChangeTag::Delete => (&mut old, from_path, i),
+                    (_, Some(i)) => (&mut new, to_path, i),
+                    _ => continue,
+                };
+                side.push(ClassifiedLine {
+                    class: if line.tag == ChangeTag::Delete { LineClass::Removed } else { LineClass::Added },
+                    tag: line.tag,
+                    path: path.to_string(),
+                    line: index + 1,
+                    text,
+                });
+            }
+            files += changed as usize;
+        }
+
+        let position = |lines: &[ClassifiedLine]| -> HashMap<(String, usize), usize> {
+            lines.iter().enumerate().map(|(i, l)| ((l.path.clone(), l.line - 1), i)).collect()
+        };
+        let (old_at, new_at) = (position(&old), position(&new));
+
+        for m in report.moves.iter().flat_map(|g| &g.blocks).map(|b| &b.block) {
+            let len = m.from_range.1 - m.from_range.0;
+            if len != m.to_range.1 - m.to_range.0 {
+                continue;
+            }
+            // line `k` of the block; negative or past `len` outside it
+            let mut pair_at = |k: isize| -> bool {
+                let (Some(o), Some(n)) = (
+                    m.from_range.0.checked_add_signed(k),
+                    m.to_range.0.checked_add_signed(k),
+                ) else {
+                    return false;
+                };
+                match (old_at.get(&(m.from_file.clone(), o)), new_at.get(&(m.to_file.clone(), n))) {
+                    (Some(&o), Some(&n)) => pair(&mut old[o], &mut new[n]),
+                    _ => false,
+                }
+            };
+
+            for k in 0..len as isize {
+                pair_at(k);
+            }
+            let mut k = -1;
+            while pair_at(k) {
+                k -= 1;
+            }
+            let mut k = len as isize;
+            while pair_at(k) {
+                k += 1;
+            }
+        }
+
+        old.extend(new);
+        LineChanges { files, lines: old }
+    }
+
+    /// Number of `class` lines on one side (`Delete` or `Insert`).
+    pub fn count(&self, class: LineClass, tag: ChangeTag) -> usize {
+        self.lines.iter().filter(|l| l.class == class && l.tag == tag).count()
+    }
+
+    /// Lines that are genuinely added or removed.
+    pub fn residual(&self) -> impl Iterator<Item = &ClassifiedLine> {
+        self.lines
+            .iter()
+            .filter(|l| matches!(l.class, LineClass::Added | LineClass::Removed))
+    }
+
+    /// True when every changed line was moved or reformatted.
+    pub fn is_move_only(&self) -> bool {
+        self.residual().next().is_none()
+    }
+}
+
+/// What a report changes, in lines; moved and reformatted lines count once,
+/// on the side they left.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChangeTally {
+    /// Files added, removed, renamed, copied or with a change not ignored
+    pub files: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub moved: usize,
+}
+
+impl From<&LineChanges> for ChangeTally {
+    fn from(changes: &LineChanges) -> Self {
+        ChangeTally {
+            files: changes.files,
+            added: changes.count(LineClass::Added, ChangeTag::Insert),
+            removed: changes.count(LineClass::Removed, ChangeTag::Delete),
+            moved: changes.count(LineClass::Moved, ChangeTag::Delete)
+                + changes.count(LineClass::Reformatted, ChangeTag::Delete),
+        }
+    }
+}
+
+impl ChangeTally {
+    pub fn has_differences(&self) -> bool {
+        self.files > 0 || self.moved > 0
+    }
+
+    /// Line count behind one `--fail-on` policy.
+    pub fn count(&self, policy: FailOn) -> usize {
+        match policy {
+            FailOn::Moved => self.moved,
+            FailOn::Added => self.added,
+            FailOn::Removed => self.removed,
+        }
+    }
+
+    /// The policies in `fail_on` that this tally violates, with their counts.
+    pub fn violations(&self, fail_on: &[FailOn]) -> Vec<(FailOn, usize)> {
+        fail_on
+            .iter()
+            .map(|&p| (p, self.count(p)))
+            .filter(|&(_, n)| n > 0)
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::options::HunkOptions;
+    use crate::types::{BlockMatch, BlockWithVote, FileDiff, GroupedMatches, SourceFile};
+
+    fn tally_of(report: &DiffReport) -> ChangeTally {
+        ChangeTally::from(&LineChanges::of(report))
+    }
+
+    fn source(path: &str, content: &str) -> Option<SourceFile> {
+        Some(SourceFile { rel: path.into(), path: path.into(), content: content.into() })
+    }
+
+    fn moved_helper() -> DiffReport {
+        DiffReport {
+            files: vec![
+                FileDiff { status: FileStatus::Modified, from: source("a.rs", "keep\n\nfn h() {\n}\n"), to: source("a.rs", "keep\n"), similarity: None },
+                FileDiff { status: FileStatus::Added, from: None, to: source("b.rs", "fn h() {\n}\n"), similarity: None },
+            ],
+            moves: vec![GroupedMatches {
+                from_file: "a.rs".into(),
+                to_file: "b.rs".into(),
+                blocks: vec![BlockWithVote {
+                    block: BlockMatch {
+                        from_file: "a.rs".into(),
+                        from_range: (2, 4),
+                        to_file: "b.rs".into(),
+                        to_range: (0, 2),
+                        similarity: 100,
+                    },
+                    is_addition: false,
+                }],
+                from_content: String::new(),
+            }],
+            hunks: HunkOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_pure_move_adds_and_removes_nothing() {
+        let tally = tally_of(&moved_helper());
+        assert_eq!(tally, ChangeTally { files: 2, added: 0, removed: 0, moved: 2 });
+        assert!(tally.has_differences());
+        assert_eq!(tally.violations(&[FailOn::Added, FailOn::Removed]), vec![]);
+        assert_eq!(tally.violations(&[FailOn::Moved]), vec![(FailOn::Moved, 2)]);
+    }
+
+    #[test]
+    fn test_lines_outside_moves_count() {
+        let mut report = moved_helper();
+        report.files[1].to = source("b.rs", "use x;\nfn h() {\n}\n");
+        report.moves[0].blocks[0].block.to_range = (1, 3);
+        report.files[0].to = source("a.rs", "kept\n");
+
+        let tally = tally_of(&report);
+        assert_eq!((tally.added, tally.removed), (2, 1));
+
+        // text that merely reappears elsewhere, outside any block, is an edit
+        report.files[1].to = source("b.rs", "keep\nfn h() {\n}\n");
+        let tally = tally_of(&report);
+        assert_eq!((tally.added, tally.removed, tally.moved), (2, 1, 2));
+
+        let ws = DiffReport { files: vec![FileDiff {
+            status: FileStatus::Modified,
+            from: source("c.rs", "x\n"),
+            to: source("c.rs", "  x\n"),
+            similarity: None,
+        }], moves: Vec::new(), hunks: HunkOptions { ignore_whitespace: true, ..HunkOptions::default() } };
+        assert!(!tally_of(&ws).has_differences());
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use crate::types::{FileDiff, FileStatus, SourceFile};
+
+use super::matcher::find_block_matches_multi;
+use super::normalize::MatchMode;
+use super::structural::Boundaries;
+
+/// Lines that can take part in a block match (boundaries never do).
+fn content_lines<'a>(text: &'a str, path: &str) -> Vec<&'a str> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mask = Boundaries::from_path(path).mask(&lines);
+    lines.into_iter().zip(mask).filter(|(_, b)| !b).map(|(l, _)| l).collect()
+}
+
+/// Upper bound on `similarity`: shared content lines, ignoring order.
+fn line_overlap(old: &SourceFile, new: &SourceFile) -> u8 {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut old_total = 0;
+    for l in content_lines(&old.content, &old.path) {
+        *counts.entry(l).or_default() += 1;
+        old_total += 1;
+    }
+
+    let mut common = 0;
+    let mut new_total = 0;
+    for l in content_lines(&new.content, &new.path) {
+        new_total += 1;
+        if let Some(c) = counts.get_mut(l).filter(|c| **c > 0) {
+            *c -= 1;
+            common += 1;
+        }
+    }
+
+    percent(common, old_total.max(new_total))
+}
+
+fn percent(matched: usize, total: usize) -> u8 {
+    (100 * matched).checked_div(total).unwrap_or(0).min(100) as u8
+}
+
+/// Similarity (0–100) of two file contents: the content lines covered by
+/// block matches, relative to the larger file (as git measures it), using
+/// the boundary rules of each file's language. Reordered
+/// code still counts; identical contents are always 100.
+pub fn similarity(old: &SourceFile, new: &SourceFile) -> u8 {
+    if old.content == new.content {
+        return 100;
+    }
+
+    let total = content_lines(&old.content, &old.path)
+        .len()
+        .max(content_lines(&new.content, &new.path).len());
+    let matches = find_block_matches_multi(
+        &[(old.path.clone(), old.content.clone())],
+        &[(new.path.clone(), new.content.clone())],
+        MatchMode::Exact,
+    );
+    let matched: usize = matches.iter().map(|m| m.from_range.1 - m.from_range.0).sum();
+
+    percent(matched, total).min(99)
+}
+
+/// Candidate pairs above `threshold`, best first. Cheap overlap is checked
+/// before the block matcher runs.
+fn score_pairs(
+    sources: &[&SourceFile],
+    targets: &[&SourceFile],
+    threshold: u8,
+) -> Vec<(u8, usize, usize)> {
+    let mut scored = Vec::new();
+
+    for (si, s) in sources.iter().enumerate() {
+        for (ti, t) in targets.iter().enumerate() {
+            if s.content.is_empty() || t.content.is_empty() {
+                continue;
+            }
+            if s.content != t.content && line_overlap(s, t) < threshold {
+                continue;
+            }
+            let sim = similarity(s, t);
+            if sim >= threshold {
+                scored.push((sim, si, ti));
+            }
+        }
+    }
+
+    // highest similarity first; ties keep path order
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+    scored
+}
+
+/// Turn removed + added pairs into renames and, with `copies`, remaining
+/// added files that derive from a surviving old file into copies. Empty files
+/// are never paired. Explicitly paired single files stay paired whatever
+/// their similarity; `threshold` only governs pairing inside directories.
+pub fn detect_renames(files: Vec<FileDiff>, threshold: u8, copies: bool) -> Vec<FileDiff> {
+    let (mut removed, mut added, mut rest): (Vec<FileDiff>, Vec<FileDiff>, Vec<FileDiff>) =
+        (Vec::new(), Vec::new(), Vec::new());
+
+    for mut d in files {
+        // explicitly paired single files with different names
+        if let (FileStatus::Renamed, None, Some(f), Some(t)) = (d.status, d.similarity, &d.from, &d.to) {
+            d.similarity = Some(similarity(f, t));
+        }
+
+        match d.status {
+            FileStatus::Removed => removed.push(d),
+            FileStatus::Added => added.push(d),
+            _ => rest.push(d),
+        }
+    }
+
+    // Renames: removed × added
+    let mut taken_from = vec![false; removed.len()];
+    let mut target: Vec<Option<(FileStatus, SourceFile, u8)>> = vec![None; added.len()];
+    {
+        let sources: Vec<&SourceFile> = removed.iter().filter_map(|d| d.from.as_ref()).collect();
+        let targets: Vec<&SourceFile> = added.iter().filter_map(|d| d.to.as_ref()).collect();
+
+        for (sim, si, ti) in score_pairs(&sources, &targets, threshold) {
+            if taken_from[si] || target[ti].is_some() {
+                continue;
+            }
+            taken_from[si] = true;
+            target[ti] = Some((FileStatus::Renamed, sources[si].clone(), sim));
+        }
+    }
+
+    // Copies: still-unmatched added × old files that survive
+    if copies {
+        let sources: Vec<&SourceFile> = rest.iter().filter_map(|d| d.from.as_ref()).collect();
+        let open: Vec<usize> = (0..added.len()).filter(|&i| target[i].is_none()).collect();
+        let targets: Vec<&SourceFile> = open.iter().filter_map(|&i| added[i].to.as_ref()).collect();
+
+        for (sim, si, ti) in score_pairs(&sources, &targets, threshold) {
+            let slot = &mut target[open[ti]];
+            if slot.is_none() {
+                *slot = Some((FileStatus::Copied, sources[si].clone(), sim));
+            }
+        }
+    }
+
+    let mut out = rest;
+    out.extend(removed.into_iter().zip(taken_from).filter(|(_, taken)| !taken).map(|(d, _)| d));
+
+    for (mut d, found) in added.into_iter().zip(target) {
+        if let Some((status, from, sim)) = found {
+            d.status = status;
+            d.from = Some(from);
+            d.similarity = Some(sim);
+        }
+        out.push(d);
+    }
+
+    out.sort_by(|a, b| a.rel().cmp(b.rel()));
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::pairing::pair_files;
+
+    fn file(root: &str, rel: &str, content: &str) -> SourceFile {
+        SourceFile { rel: rel.into(), path: format!("{}/{}", root, rel), content: content.into() }
+    }
+
+    const BODY: &str = "def g():\n    x = 1\n    y = 2\n    z = 3\n    w = 4\n    return x + y + z + w\n";
+
+    #[test]
+    fn test_similarity_bounds() {
+        let body = file("old", "a.py", BODY);
+        assert_eq!(similarity(&body, &body), 100);
+        assert_eq!(similarity(&body, &file("new", "a.py", "def h():\n    pass\n")), 0);
+        let tweaked = file("new", "a.py", &BODY.replace("w = 4", "w = 5"));
+        let sim = similarity(&body, &tweaked);
+        assert!((70..100).contains(&sim), "{}", sim);
+    }
+
+    #[test]
+    fn test_rename_and_copy_detection() {
+        let tweaked = BODY.replace("w = 4", "w = 5");
+        let from = vec![
+            file("old", "keep.py", BODY),
+            file("old", "moved.py", "a = 1\nb = 2\nc = 3\nd = 4\n"),
+            file("old", "gone.py", "unrelated = True\n"),
+        ];
+        let to = vec![
+            file("new", "keep.py", BODY),
+            file("new", "pkg/moved.py", "a = 1\nb = 2\nc = 3\nd = 4\n"),
+            file("new", "copy.py", &tweaked),
+            file("new", "fresh.py", "brand_new = 1\n"),
+        ];
+
+        let diffs = detect_renames(pair_files(&from, &to, false), 50, true);
+        let summary: Vec<(&str, FileStatus, Option<u8>)> =
+            diffs.iter().map(|d| (d.rel(), d.status, d.similarity)).collect();
+
+        assert_eq!(summary[0].0, "copy.py");
+        assert_eq!(summary[0].1, FileStatus::Copied);
+        assert_eq!(diffs[0].from.as_ref().unwrap().rel, "keep.py");
+        assert_eq!(summary[1], ("fresh.py", FileStatus::Added, None));
+        assert_eq!(summary[2], ("gone.py", FileStatus::Removed, None));
+        assert_eq!(summary[3], ("keep.py", FileStatus::Unchanged, None));
+        assert_eq!(summary[4], ("pkg/moved.py", FileStatus::Renamed, Some(100)));
+        assert_eq!(diffs[4].from.as_ref().unwrap().path, "old/moved.py");
+
+        let no_copies = detect_renames(pair_files(&from, &to, false), 50, false);
+        assert_eq!(no_copies[0].status, FileStatus::Added);
+    }
+
+    #[test]
+    fn test_explicit_pairs_ignore_the_threshold() {
+        let old = [file("old", "a.py", BODY)];
+        let similar = [file("new", "b.py", &BODY.replace("w = 4", "w = 5"))];
+        let unrelated = [file("new", "b.py", "print('hello')\n")];
+
+        let kept = detect_renames(pair_files(&old, &similar, true), 50, false);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].status, FileStatus::Renamed);
+
+        let kept = detect_renames(pair_files(&old, &unrelated, true), 50, false);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].status, FileStatus::Renamed);
+        assert_eq!(kept[0].similarity, Some(0));
+    }
+}
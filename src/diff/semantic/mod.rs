@@ -0,0 +1,346 @@
+pub mod python;
+pub mod rust;
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::types::{DiffReport, SourceFile};
+use super::structural::Boundaries;
+
+/// Bodies shorter than this (`pass`, `self.x`) are too common to tell that
+/// an item was renamed.
+const MIN_RENAME_BODY: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    Function,
+    Class,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    Module,
+    Const,
+    Static,
+    TypeAlias,
+    Macro,
+}
+
+impl ItemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemKind::Function => "function",
+            ItemKind::Class => "class",
+            ItemKind::Struct => "struct",
+            ItemKind::Enum => "enum",
+            ItemKind::Union => "union",
+            ItemKind::Trait => "trait",
+            ItemKind::Module => "mod",
+            ItemKind::Const => "const",
+            ItemKind::Static => "static",
+            ItemKind::TypeAlias => "type",
+            ItemKind::Macro => "macro",
+        }
+    }
+}
+
+/// A function, class or type definition found in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub kind: ItemKind,
+    /// Qualified name: `Class.method` in Python, `Type::method` in Rust
+    pub name: String,
+    /// Header up to the body, decorators / attributes included, whitespace
+    /// collapsed
+    pub signature: String,
+    /// Body lines trimmed, without comments and blank lines
+    pub body: String,
+    /// 1-based line of the `def` / `fn` / … keyword
+    pub line: usize,
+}
+
+impl Item {
+    /// Name without the enclosing class / type.
+    pub fn short_name(&self) -> &str {
+        self.name.rsplit(['.', ':']).next().unwrap_or(&self.name)
+    }
+}
+
+/// Collapse runs of whitespace to single spaces.
+fn collapse(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn normalize_body(s: &str) -> String {
+    s.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n")
+}
+
+/// Items of a Python or Rust file; `None` for other languages.
+pub fn items_of(file: &SourceFile) -> Option<Vec<Item>> {
+    match Boundaries::from_path(&file.path) {
+        Boundaries::Python => Some(python::items(&file.content)),
+        Boundaries::Rust => Some(rust::items(&file.content)),
+        _ => None,
+    }
+}
+
+/// An item on one side of the diff, with the file it is in.
+#[derive(Debug, Clone)]
+pub struct Located {
+    pub path: String,
+    pub item: Item,
+}
+
+/// How an item changed, most telling first: a renamed item may also have
+/// moved or been edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Renamed,
+    /// Now in a different file
+    Moved,
+    /// Same name and file; signature or body edited
+    Modified,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Renamed => "renamed",
+            ChangeKind::Moved => "moved",
+            ChangeKind::Modified => "modified",
+        }
+    }
+}
+
+/// One changed item: old and new version (one missing for additions and
+/// removals).
+#[derive(Debug, Clone)]
+pub struct ItemChange {
+    pub old: Option<Located>,
+    pub new: Option<Located>,
+    /// The two versions are in different file pairs
+    pub moved: bool,
+}
+
+impl ItemChange {
+    /// The new version, or the old one for removals.
+    pub fn current(&self) -> &Located {
+        self.new.as_ref().or(self.old.as_ref()).expect("a change has at least one side")
+    }
+
+    pub fn kind(&self) -> ItemKind {
+        self.current().item.kind
+    }
+
+    pub fn change(&self) -> ChangeKind {
+        match (&self.old, &self.new) {
+            (None, _) => ChangeKind::Added,
+            (_, None) => ChangeKind::Removed,
+            _ if self.is_renamed() => ChangeKind::Renamed,
+            _ if self.moved => ChangeKind::Moved,
+            _ => ChangeKind::Modified,
+        }
+    }
+
+    pub fn is_renamed(&self) -> bool {
+        matches!((&self.old, &self.new), (Some(o), Some(n)) if o.item.name != n.item.name)
+    }
+
+    /// Signature differs other than by the rename itself.
+    pub fn signature_changed(&self) -> bool {
+        let (Some(o), Some(n)) = (&self.old, &self.new) else {
+            return false;
+        };
+        let old = o.item.signature.replace(o.item.short_name(), n.item.short_name());
+        old != n.item.signature
+    }
+
+    pub fn body_changed(&self) -> bool {
+        matches!((&self.old, &self.new), (Some(o), Some(n)) if o.item.body != n.item.body)
+    }
+
+    fn is_change(&self) -> bool {
+        self.old.is_none() || self.new.is_none() || self.moved || self.is_renamed() || self.signature_changed() || self.body_changed()
+    }
+}
+
+/// Item-level changes of a diff.
+#[derive(Debug, Clone, Default)]
+pub struct SemanticDiff {
+    /// In file order of the report, then by line
+    pub changes: Vec<ItemChange>,
+    /// Changed files in languages that are not parsed
+    pub skipped: Vec<String>,
+}
+
+/// Item of one side, with the index of its file pair in the report.
+type Side = (usize, Located);
+
+/// Pair untaken items of `old` and `new` with equal keys, in order.
+fn pair_by<K: Hash + Eq>(
+    old: &[Side],
+    new: &[Side],
+    taken: (&mut [bool], &mut [bool]),
+    key: impl Fn(&Side) -> Option<K>,
+) -> Vec<(usize, usize)> {
+    let (old_taken, new_taken) = taken;
+    let mut candidates: HashMap<K, Vec<usize>> = HashMap::new();
+    for (i, side) in old.iter().enumerate().rev().filter(|(i, _)| !old_taken[*i]) {
+        if let Some(k) = key(side) {
+            candidates.entry(k).or_default().push(i);
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for (j, side) in new.iter().enumerate() {
+        if new_taken[j] {
+            continue;
+        }
+        if let Some(i) = key(side).and_then(|k| candidates.get_mut(&k)?.pop()) {
+            old_taken[i] = true;
+            new_taken[j] = true;
+            pairs.push((i, j));
+        }
+    }
+    pairs
+}
+
+/// Parse both sides of every changed Python / Rust file and match their
+/// items: by name within the same file pair, then by name anywhere (a move),
+/// then by identical body (a rename). What is left was added or removed.
+pub fn semantic_diff(report: &DiffReport) -> SemanticDiff {
+    let mut diff = SemanticDiff::default();
+    let (mut old, mut new): (Vec<Side>, Vec<Side>) = (Vec::new(), Vec::new());
+
+    for (n, d) in report.files.iter().enumerate() {
+        if matches!((&d.from, &d.to), (Some(f), Some(t)) if f.content == t.content) {
+            continue;
+        }
+        let mut parsed = false;
+        for (side, out) in [(&d.from, &mut old), (&d.to, &mut new)] {
+            let Some(file) = side else {
+                continue;
+            };
+            if let Some(items) = items_of(file) {
+                parsed = true;
+                out.extend(items.into_iter().map(|item| (n, Located { path: file.path.clone(), item })));
+            }
+        }
+        if !parsed {
+            diff.skipped.push(d.rel().to_string());
+        }
+    }
+
+    let mut old_taken = vec![false; old.len()];
+    let mut new_taken = vec![false; new.len()];
+    let mut pairs = pair_by(&old, &new, (&mut old_taken, &mut new_taken), |(n, l)| {
+        Some((*n, l.item.kind, l.item.name.clone()))
+    });
+    pairs.extend(pair_by(&old, &new, (&mut old_taken, &mut new_taken), |(_, l)| {
+        Some((l.item.kind, l.item.name.clone()))
+    }));
+    pairs.extend(pair_by(&old, &new, (&mut old_taken, &mut new_taken), |(_, l)| {
+        (l.item.body.len() >= MIN_RENAME_BODY).then(|| (l.item.kind, l.item.body.clone()))
+    }));
+
+    let mut changes: Vec<(usize, ItemChange)> = pairs
+        .into_iter()
+        .map(|(i, j)| {
+            let change = ItemChange { old: Some(old[i].1.clone()), new: Some(new[j].1.clone()), moved: old[i].0 != new[j].0 };
+            (new[j].0, change)
+        })
+        .filter(|(_, c)| c.is_change())
+        .collect();
+    changes.extend(old.iter().zip(&old_taken).filter(|(_, t)| !**t).map(|((n, l), _)| {
+        (*n, ItemChange { old: Some(l.clone()), new: None, moved: false })
+    }));
+    changes.extend(new.iter().zip(&new_taken).filter(|(_, t)| !**t).map(|((n, l), _)| {
+        (*n, ItemChange { old: None, new: Some(l.clone()), moved: false })
+    }));
+
+    changes.sort_by_key(|(n, c)| (*n, c.current().item.line));
+    diff.changes = changes.into_iter().map(|(_, c)| c).collect();
+    diff
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileDiff, FileStatus};
+
+    fn source(path: &str, content: &str) -> Option<SourceFile> {
+        Some(SourceFile { rel: path.into(), path: path.into(), content: content.into() })
+    }
+
+    fn change_of<'a>(diff: &'a SemanticDiff, name: &str) -> &'a ItemChange {
+        diff.changes.iter().find(|c| c.current().item.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_item_level_changes() {
+        let old_app = "\
+def load(path):
+    return open(path).read()
+
+def parse(text):
+    return text.split()
+
+def legacy():
+    pass
+
+def fetch(url):
+    # retry later
+    return client.get(url, timeout=30)
+
+def helper(x):
+    return x * 2 + offset(x)
+";
+        let new_app = "\
+def load(path):
+    return open(path).read()  # comments do not count
+
+def parse(text, strict=False):
+    return text.split()
+
+def fetch_all(url):
+    return client.get(url, timeout=30)
+
+def save(data):
+    write(data)
+";
+        let report = DiffReport {
+            files: vec![
+                FileDiff { status: FileStatus::Modified, from: source("app.py", old_app), to: source("app.py", new_app), similarity: None },
+                FileDiff { status: FileStatus::Added, from: None, to: source("util.py", "def helper(x):\n    return x * 3 + offset(x)\n"), similarity: None },
+                FileDiff { status: FileStatus::Added, from: None, to: source("notes.md", "# notes\n"), similarity: None },
+            ],
+            ..DiffReport::default()
+        };
+
+        let diff = semantic_diff(&report);
+        let summary: Vec<(&str, ChangeKind)> = diff.changes.iter().map(|c| (c.current().item.name.as_str(), c.change())).collect();
+        assert_eq!(
+            summary,
+            [
+                ("parse", ChangeKind::Modified),
+                ("fetch_all", ChangeKind::Renamed),
+                ("legacy", ChangeKind::Removed),
+                ("save", ChangeKind::Added),
+                ("helper", ChangeKind::Moved),
+            ]
+        );
+
+        let parse = change_of(&diff, "parse");
+        assert!(parse.signature_changed() && !parse.body_changed());
+        let fetch = change_of(&diff, "fetch_all");
+        assert!(!fetch.signature_changed() && !fetch.body_changed());
+        let helper = change_of(&diff, "helper");
+        assert!(helper.moved && helper.body_changed());
+        assert_eq!(diff.skipped, ["notes.md"]);
+    }
+}
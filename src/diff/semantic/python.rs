@@ -0,0 +1,243 @@
+use super::{collapse, normalize_body, Item, ItemKind};
+
+/// Two views of `src`, byte for byte the same length: `code` with string
+/// literals and comments blanked (for structure), `text` with only comments
+/// blanked (for comparing). Newlines are always kept.
+fn blank(src: &str) -> (String, String) {
+    let b = src.as_bytes();
+    let (mut code, mut text) = (b.to_vec(), b.to_vec());
+    let clear = |buf: &mut Vec<u8>, from: usize, to: usize| {
+        buf[from..to].iter_mut().filter(|c| **c != b'\n').for_each(|c| *c = b' ');
+    };
+
+    let mut i = 0;
+    while i < b.len() {
+        match b[i] {
+            b'#' => {
+                let end = b[i..].iter().position(|&c| c == b'\n').map_or(b.len(), |n| i + n);
+                clear(&mut code, i, end);
+                clear(&mut text, i, end);
+                i = end;
+            }
+            quote @ (b'\'' | b'"') => {
+                let triple = b[i..].starts_with(&[quote; 3]);
+                let start = i;
+                i += if triple { 3 } else { 1 };
+                while i < b.len() {
+                    if b[i] == b'\\' {
+                        i += 2;
+                    } else if triple && b[i..].starts_with(&[quote; 3]) {
+                        i += 3;
+                        break;
+                    } else if !triple && (b[i] == quote || b[i] == b'\n') {
+                        i += (b[i] == quote) as usize;
+                        break;
+                    } else {
+                        i += 1;
+                    }
+                }
+                i = i.min(b.len());
+                clear(&mut code, start, i);
+            }
+            _ => i += 1,
+        }
+    }
+
+    let utf8 = |buf: Vec<u8>| String::from_utf8(buf).unwrap_or_default();
+    (utf8(code), utf8(text))
+}
+
+/// Indentation width of a line, `None` when it is blank (tabs count as 8).
+fn indent(line: &str) -> Option<usize> {
+    if line.trim().is_empty() {
+        return None;
+    }
+    Some(
+        line.chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 8 } else { 1 })
+            .sum(),
+    )
+}
+
+struct Lines<'a> {
+    code: Vec<&'a str>,
+    text: Vec<&'a str>,
+}
+
+impl Lines<'_> {
+    /// First `:` at bracket depth 0 from line `i` on: `(line, byte column)`.
+    fn header_colon(&self, i: usize, end: usize) -> Option<(usize, usize)> {
+        let mut depth = 0i32;
+        for (n, line) in self.code[i..end].iter().enumerate() {
+            for (col, c) in line.bytes().enumerate() {
+                match c {
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' => depth -= 1,
+                    b':' if depth == 0 => return Some((i + n, col)),
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// Last line of the statement starting at `i` (brackets may span lines).
+    fn statement_end(&self, i: usize, end: usize) -> usize {
+        let mut depth = 0i32;
+        for (n, line) in self.code[i..end].iter().enumerate() {
+            for c in line.bytes() {
+                match c {
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' => depth -= 1,
+                    _ => {}
+                }
+            }
+            if depth <= 0 {
+                return i + n;
+            }
+        }
+        end - 1
+    }
+
+    /// Collect the `def` / `class` items of `start..end` into `out`; returns
+    /// the line ranges they cover.
+    fn block(&self, start: usize, end: usize, prefix: &str, out: &mut Vec<Item>) -> Vec<(usize, usize)> {
+        let mut covered = Vec::new();
+        let mut decorators = None;
+        let mut i = start;
+
+        while i < end {
+            let Some(ind) = indent(self.code[i]) else {
+                i += 1;
+                continue;
+            };
+            let line = self.code[i].trim_start();
+
+            if line.starts_with('@') {
+                decorators.get_or_insert(i);
+                i = self.statement_end(i, end) + 1;
+                continue;
+            }
+
+            let (kind, rest) = if let Some(rest) = line.strip_prefix("def ").or_else(|| line.strip_prefix("async def ")) {
+                (ItemKind::Function, rest)
+            } else if let Some(rest) = line.strip_prefix("class ") {
+                (ItemKind::Class, rest)
+            } else {
+                decorators = None;
+                i = self.statement_end(i, end) + 1;
+                continue;
+            };
+
+            let name: String = rest.trim_start().chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+            let first = decorators.take().unwrap_or(i);
+            let Some((header, col)) = self.header_colon(i, end) else {
+                i += 1;
+                continue;
+            };
+
+            // `def f(): return 1` keeps its body on the header line
+            let inline = !self.code[header][col + 1..].trim().is_empty();
+            let mut last = header;
+            if !inline {
+                for j in header + 1..end {
+                    match indent(self.code[j]) {
+                        None => {}
+                        Some(k) if k > ind => last = j,
+                        Some(_) => break,
+                    }
+                }
+            }
+
+            let signature: Vec<&str> = self.text[first..header]
+                .iter()
+                .copied()
+                .chain(std::iter::once(&self.text[header][..col + 1]))
+                .collect();
+            let name = format!("{}{}", prefix, name);
+
+            let index = out.len();
+            out.push(Item {
+                kind,
+                name: name.clone(),
+                signature: collapse(&signature.join(" ")),
+                body: String::new(),
+                line: i + 1,
+            });
+
+            let nested = match kind {
+                ItemKind::Class => self.block(header + 1, last + 1, &format!("{}.", name), out),
+                _ => Vec::new(),
+            };
+            let body = std::iter::once(&self.text[header][col + 1..])
+                .chain(
+                    (header + 1..=last)
+                        .filter(|j| !nested.iter().any(|&(s, e)| (s..e).contains(j)))
+                        .map(|j| self.text[j]),
+                )
+                .collect::<Vec<_>>()
+                .join("\n");
+            out[index].body = normalize_body(&body);
+
+            covered.push((first, last + 1));
+            i = last + 1;
+        }
+
+        covered
+    }
+}
+
+/// Top-level functions and classes of a Python module, methods (and nested
+/// classes) named `Class.method`. Functions nested in functions are part of
+/// their parent's body.
+pub fn items(src: &str) -> Vec<Item> {
+    let (code, text) = blank(src);
+    let lines = Lines { code: code.lines().collect(), text: text.lines().collect() };
+
+    let mut out = Vec::new();
+    lines.block(0, lines.code.len(), "", &mut out);
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_items() {
+        let src = "\
+import os
+
+@cache
+def load(path,
+         mode='r'):  # open it
+    \"\"\"Docstring
+def not_an_item():
+    \"\"\"
+    return open(path, mode)
+
+class Store(Base):
+    size = 3
+
+    def get(self, key): return self.data[key]
+
+    async def put(self, key, value):
+        def inner():
+            pass
+        self.data[key] = value
+";
+        let items = items(src);
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["load", "Store", "Store.get", "Store.put"]);
+
+        assert_eq!(items[0].signature, "@cache def load(path, mode='r'):");
+        assert_eq!(items[0].line, 4);
+        assert!(items[0].body.contains("return open(path, mode)"));
+        assert_eq!(items[1].kind, ItemKind::Class);
+        assert_eq!(items[1].body, "size = 3");
+        assert_eq!(items[2].body, "return self.data[key]");
+        assert!(items[3].body.starts_with("def inner():"));
+    }
+}
@@ -0,0 +1,358 @@
+use super::{collapse, normalize_body, Item, ItemKind};
+
+fn is_ident(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}
+
+/// Two views of `src`, byte for byte the same length: `code` with string and
+/// char literals and comments blanked (for structure), `text` with only
+/// comments blanked (for comparing). Newlines are always kept.
+fn blank(src: &str) -> (Vec<u8>, String) {
+    let b = src.as_bytes();
+    let (mut code, mut text) = (b.to_vec(), b.to_vec());
+    let clear = |buf: &mut Vec<u8>, from: usize, to: usize| {
+        buf[from..to].iter_mut().filter(|c| **c != b'\n').for_each(|c| *c = b' ');
+    };
+
+    let mut i = 0;
+    while i < b.len() {
+        let start = i;
+        let comment = match b[i] {
+            b'/' if b.get(i + 1) == Some(&b'/') => {
+                i = b[i..].iter().position(|&c| c == b'\n').map_or(b.len(), |n| i + n);
+                true
+            }
+            b'/' if b.get(i + 1) == Some(&b'*') => {
+                let mut depth = 0;
+                while i < b.len() {
+                    if b[i..].starts_with(b"/*") {
+                        depth += 1;
+                        i += 2;
+                    } else if b[i..].starts_with(b"*/") {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+                true
+            }
+            b'r' if (i == 0 || !is_ident(b[i - 1]) || (b[i - 1] == b'b' && (i < 2 || !is_ident(b[i - 2])))) => {
+                let hashes = b[i + 1..].iter().take_while(|&&c| c == b'#').count();
+                if b.get(i + 1 + hashes) != Some(&b'"') {
+                    i += 1;
+                    continue;
+                }
+                let mut close = vec![b'"'];
+                close.resize(1 + hashes, b'#');
+                i += 2 + hashes;
+                i = b[i..].windows(close.len()).position(|w| w == close.as_slice()).map_or(b.len(), |n| i + n + close.len());
+                false
+            }
+            b'"' => {
+                i += 1;
+                while i < b.len() && b[i] != b'"' {
+                    i += if b[i] == b'\\' { 2 } else { 1 };
+                }
+                i = (i + 1).min(b.len());
+                false
+            }
+            // a char literal, unless it is a lifetime
+            b'\'' => {
+                let width = src[i + 1..].chars().next().map_or(1, char::len_utf8);
+                if b.get(i + 1) == Some(&b'\\') {
+                    i = b[i + 2..].iter().position(|&c| c == b'\'').map_or(b.len(), |n| i + n + 3);
+                } else if b.get(i + 1 + width) == Some(&b'\'') {
+                    i += width + 2;
+                } else {
+                    i += 1;
+                    continue;
+                }
+                false
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        clear(&mut code, start, i);
+        if comment {
+            clear(&mut text, start, i);
+        }
+    }
+
+    (code, String::from_utf8(text).unwrap_or_default())
+}
+
+struct Parser<'a> {
+    code: Vec<u8>,
+    text: &'a str,
+}
+
+impl Parser<'_> {
+    fn skip_ws(&self, mut pos: usize, end: usize) -> usize {
+        while pos < end && self.code[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        pos
+    }
+
+    fn ident_at(&self, pos: usize, end: usize) -> Option<(&str, usize)> {
+        let len = self.code[pos..end].iter().take_while(|&&c| is_ident(c)).count();
+        let word = std::str::from_utf8(&self.code[pos..pos + len]).ok()?;
+        (len > 0).then_some((word, pos + len))
+    }
+
+    /// Position of the bracket closing the one at `open` (or `end`).
+    fn closing(&self, open: usize, end: usize) -> usize {
+        let mut depth = 0i32;
+        for pos in open..end {
+            match self.code[pos] {
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return pos;
+                    }
+                }
+                _ => {}
+            }
+        }
+        end
+    }
+
+    /// First `{` or `;` outside brackets from `pos`; with `braces`, blocks
+    /// are stepped over too, so only `;` ends the search.
+    fn header_end(&self, mut pos: usize, end: usize, braces: bool) -> usize {
+        while pos < end {
+            match self.code[pos] {
+                b'{' if !braces => return pos,
+                b'(' | b'[' | b'{' => pos = self.closing(pos, end),
+                b';' => return pos,
+                _ => {}
+            }
+            pos += 1;
+        }
+        end
+    }
+
+    fn line_of(&self, pos: usize) -> usize {
+        self.code[..pos].iter().filter(|&&c| c == b'\n').count() + 1
+    }
+
+    /// Items of `start..end` into `out`, names prefixed with `prefix`.
+    /// Bodies of functions are not searched.
+    fn items(&self, start: usize, end: usize, prefix: &str, out: &mut Vec<Item>) {
+        let mut pos = self.skip_ws(start, end);
+
+        while pos < end {
+            let first = pos;
+            while pos < end && self.code[pos] == b'#' {
+                let open = self.skip_ws(pos + 1 + (self.code.get(pos + 1) == Some(&b'!')) as usize, end);
+                if self.code.get(open) != Some(&b'[') {
+                    break;
+                }
+                pos = self.skip_ws(self.closing(open, end) + 1, end);
+            }
+
+            let mut keyword = self.ident_at(pos, end);
+            while let Some((word, after)) = keyword {
+                let after = self.skip_ws(after, end);
+                let next = self.ident_at(after, end).map(|(w, _)| w);
+                match word {
+                    "pub" if self.code.get(after) == Some(&b'(') => pos = self.skip_ws(self.closing(after, end) + 1, end),
+                    "pub" | "async" | "unsafe" | "default" | "extern" => pos = after,
+                    "const" if matches!(next, Some("fn" | "unsafe" | "async" | "extern")) => pos = after,
+                    _ => break,
+                }
+                keyword = self.ident_at(pos, end);
+            }
+
+            let Some((word, after)) = keyword else {
+                pos = self.skip_statement(pos, end);
+                continue;
+            };
+            let kind = match word {
+                "fn" => ItemKind::Function,
+                "struct" => ItemKind::Struct,
+                "enum" => ItemKind::Enum,
+                "union" => ItemKind::Union,
+                "trait" => ItemKind::Trait,
+                "mod" => ItemKind::Module,
+                "const" => ItemKind::Const,
+                "static" => ItemKind::Static,
+                "type" => ItemKind::TypeAlias,
+                "macro_rules" => ItemKind::Macro,
+                "impl" => {
+                    pos = self.impl_block(after, end, prefix, out);
+                    continue;
+                }
+                _ => {
+                    pos = self.skip_statement(pos, end);
+                    continue;
+                }
+            };
+
+            let mut name_at = self.skip_ws(after, end);
+            if kind == ItemKind::Macro && self.code.get(name_at) == Some(&b'!') {
+                name_at = self.skip_ws(name_at + 1, end);
+            }
+            let name = self.ident_at(name_at, end).map_or("", |(w, _)| w);
+            let name = format!("{}{}", prefix, name);
+
+            let valued = matches!(kind, ItemKind::Const | ItemKind::Static | ItemKind::TypeAlias);
+            let stop = self.header_end(name_at, end, valued);
+            let (signature, body, next) = if valued {
+                // `const X: T = value;`: the value is the body
+                let header = &self.code[first..stop];
+                let eq = header.iter().position(|&c| c == b'=').map_or(stop, |n| first + n);
+                (&self.text[first..eq], &self.text[(eq + 1).min(stop)..stop], stop + 1)
+            } else if self.code.get(stop) == Some(&b'{') {
+                let close = self.closing(stop, end);
+                (&self.text[first..stop], &self.text[stop + 1..close], close + 1)
+            } else {
+                (&self.text[first..stop], "", stop + 1)
+            };
+
+            let inline_mod = kind == ItemKind::Module && !body.is_empty();
+            if inline_mod {
+                let close = next - 1;
+                self.items(stop + 1, close, &format!("{}::", name), out);
+            } else {
+                out.push(Item {
+                    kind,
+                    name: name.clone(),
+                    signature: collapse(signature),
+                    body: if kind == ItemKind::Trait { String::new() } else { normalize_body(body) },
+                    line: self.line_of(pos),
+                });
+                if kind == ItemKind::Trait && self.code.get(stop) == Some(&b'{') {
+                    self.items(stop + 1, next - 1, &format!("{}::", name), out);
+                }
+            }
+
+            pos = self.skip_ws(next.min(end), end);
+        }
+    }
+
+    /// Step over something that is not an item (`use`, a macro call, …).
+    fn skip_statement(&self, pos: usize, end: usize) -> usize {
+        let stop = self.header_end(pos, end, false);
+        let next = match self.code.get(stop) {
+            Some(b'{') => self.closing(stop, end) + 1,
+            _ => stop + 1,
+        };
+        self.skip_ws(next.min(end), end)
+    }
+
+    /// Methods of an `impl` block become `Type::method`, or
+    /// `<Type as Trait>::method` for trait impls. Returns where parsing
+    /// continues.
+    fn impl_block(&self, after: usize, end: usize, prefix: &str, out: &mut Vec<Item>) -> usize {
+        let stop = self.header_end(after, end, false);
+        if self.code.get(stop) != Some(&b'{') {
+            return self.skip_ws((stop + 1).min(end), end);
+        }
+        let close = self.closing(stop, end);
+
+        let header = collapse(&String::from_utf8_lossy(&self.code[after..stop]));
+        let header = header.split(" where ").next().unwrap_or("");
+        // drop the impl's own generic parameters
+        let mut header = header.trim();
+        if header.starts_with('<') {
+            let mut depth = 0;
+            for (n, c) in header.char_indices() {
+                depth += match c {
+                    '<' => 1,
+                    '>' => -1,
+                    _ => 0,
+                };
+                if depth == 0 {
+                    header = header[n + 1..].trim_start();
+                    break;
+                }
+            }
+        }
+
+        let type_name = |ty: &str| {
+            let ty = ty.trim().trim_start_matches('&').trim_start_matches("dyn ").trim();
+            let ty = ty.split('<').next().unwrap_or(ty);
+            ty.rsplit("::").next().unwrap_or(ty).trim().to_string()
+        };
+        let owner = match header.split_once(" for ") {
+            Some((tr, ty)) => format!("<{} as {}>", type_name(ty), tr.trim()),
+            None => type_name(header),
+        };
+
+        self.items(stop + 1, close, &format!("{}{}::", prefix, owner), out);
+        self.skip_ws((close + 1).min(end), end)
+    }
+}
+
+/// Items of a Rust source file: functions, types, traits, constants and
+/// macros, with `impl` methods named `Type::method` and the contents of
+/// inline modules `module::item`. Items inside function bodies are part of
+/// their parent's body.
+pub fn items(src: &str) -> Vec<Item> {
+    let (code, text) = blank(src);
+    let parser = Parser { code, text: &text };
+
+    let mut out = Vec::new();
+    parser.items(0, parser.code.len(), "", &mut out);
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_items() {
+        let src = r##"use std::fmt;
+
+/// Doc comments are not part of the signature.
+#[derive(Debug)]
+pub struct Point { x: i32, y: i32 }
+
+const ORIGIN: Point = Point { x: 0, y: 0 };
+
+impl<T> fmt::Display for Wrapper<T> where T: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", '{') // a brace in a char
+    }
+}
+
+impl Point {
+    pub(crate) fn norm(&self) -> i32 {
+        fn helper() {}
+        let s = r#"fn fake() {"#;
+        self.x.abs() + self.y.abs()
+    }
+}
+
+mod inner {
+    pub trait Shape<'a> { fn area(&self) -> f64; }
+}
+"##;
+        let items = items(src);
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Point", "ORIGIN", "<Wrapper as fmt::Display>::fmt", "Point::norm", "inner::Shape", "inner::Shape::area"]
+        );
+
+        assert_eq!(items[0].signature, "#[derive(Debug)] pub struct Point");
+        assert_eq!(items[0].body, "x: i32, y: i32");
+        assert_eq!(items[0].line, 5);
+        assert_eq!(items[1].signature, "const ORIGIN: Point");
+        assert_eq!(items[1].body, "Point { x: 0, y: 0 }");
+        assert_eq!(items[2].body, "write!(f, \"{}\", '{')");
+        assert_eq!(items[3].signature, "pub(crate) fn norm(&self) -> i32");
+        assert!(items[3].body.starts_with("fn helper() {}"));
+        assert_eq!(items[5].kind, ItemKind::Function);
+    }
+}
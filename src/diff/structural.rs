@@ -1,16 +1,116 @@
-/// Detect a "structural boundary" — lines that must terminate a block.
-pub fn is_boundary(line: &str) -> bool {
-    let trimmed = line.trim_start();
-    trimmed.is_empty()
-        || trimmed.starts_with("class ")
-        || trimmed.starts_with("def ")
-        || trimmed.starts_with("@")
+use std::path::Path;
+
+/// The language-specific notion of "a line that must terminate a block".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boundaries {
+    /// `class`, `def`, decorators, blank lines
+    Python,
+    /// `fn`, `impl`, `struct`, `enum`, `trait`, `mod`, attributes, blank lines
+    Rust,
+    /// `function`, `class`, `interface`, `export`, blank lines
+    TypeScript,
+    /// `func`, `type`, blank lines
+    Go,
+    /// sectioning commands and paragraph breaks
+    Latex,
+    /// Generic fallback: top-level lines that open a `{` block, and blank
+    /// lines outside any block
+    Braces,
+}
+
+const RUST_ITEMS: &[&str] = &[
+    "fn ", "async fn ", "const fn ", "unsafe fn ", "impl ", "impl<", "struct ", "enum ",
+    "trait ", "mod ", "union ", "macro_rules!", "#[", "#![",
+];
+const TS_ITEMS: &[&str] = &[
+    "function ", "function*", "async function", "class ", "abstract class ", "interface ",
+    "export ", "@",
+];
+const GO_ITEMS: &[&str] = &["func ", "type "];
+const LATEX_ITEMS: &[&str] = &[
+    "\\part", "\\chapter", "\\section", "\\subsection", "\\subsubsection", "\\paragraph",
+];
+
+/// Strip Rust visibility so `pub(crate) fn` reads like `fn`.
+fn strip_visibility(line: &str) -> &str {
+    let Some(rest) = line.strip_prefix("pub") else { return line };
+    let rest = match rest.strip_prefix('(') {
+        Some(r) => r.split_once(')').map(|(_, tail)| tail).unwrap_or(r),
+        None => rest,
+    };
+    rest.trim_start()
+}
+
+fn starts_with_any(line: &str, prefixes: &[&str]) -> bool {
+    prefixes.iter().any(|p| line.starts_with(p))
+}
+
+impl Boundaries {
+    pub fn from_path(path: &str) -> Self {
+        // `HEAD:src/a.rs` (git sides) still ends in the real extension
+        match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some("py") | Some("pyi") => Self::Python,
+            Some("rs") => Self::Rust,
+            Some("ts") | Some("tsx") | Some("js") | Some("jsx") | Some("mjs") | Some("cjs") => Self::TypeScript,
+            Some("go") => Self::Go,
+            Some("tex") | Some("sty") | Some("cls") => Self::Latex,
+            _ => Self::Braces,
+        }
+    }
+
+    /// Whether a single line is a boundary, for strategies that need no context.
+    fn is_boundary_line(&self, line: &str) -> bool {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            return true;
+        }
+
+        match self {
+            Self::Python => {
+                trimmed.starts_with("class ")
+                    || trimmed.starts_with("def ")
+                    || trimmed.starts_with("async def ")
+                    || trimmed.starts_with('@')
+            }
+            Self::Rust => starts_with_any(strip_visibility(trimmed), RUST_ITEMS),
+            Self::TypeScript => starts_with_any(trimmed, TS_ITEMS),
+            Self::Go => starts_with_any(trimmed, GO_ITEMS),
+            Self::Latex => starts_with_any(trimmed, LATEX_ITEMS),
+            Self::Braces => false,
+        }
+    }
+
+    /// Boundary flag for every line of a file.
+    pub fn mask(&self, lines: &[&str]) -> Vec<bool> {
+        if *self != Self::Braces {
+            return lines.iter().map(|l| self.is_boundary_line(l)).collect();
+        }
+
+        let mut depth: usize = 0;
+        lines
+            .iter()
+            .map(|line| {
+                let at_top = depth == 0;
+                let opens = line.chars().filter(|&c| c == '{').count();
+                let closes = line.chars().filter(|&c| c == '}').count();
+                depth = (depth + opens).saturating_sub(closes);
+
+                at_top && (line.trim().is_empty() || opens > closes)
+            })
+            .collect()
+    }
 }
 
 /// Extend a block using structural consistency.
-pub fn extend_structural_block(
-    from_lines: &[&str],
-    to_lines: &[&str],
+///
+/// Lines are compared by their keys (raw or normalized, see `MatchMode`);
+/// `from_mask` / `to_mask` flag boundary lines (see [`Boundaries::mask`]) and
+/// a block never crosses one.
+pub fn extend_structural_block<S: AsRef<str>>(
+    from_lines: &[S],
+    to_lines: &[S],
+    from_mask: &[bool],
+    to_mask: &[bool],
     from_start: usize,
     to_start: usize,
 ) -> (usize, usize, usize, usize) {
@@ -20,8 +120,8 @@ pub fn extend_structural_block(
     let mut t2 = to_start;
 
     // Extend forward
-    while f2 < from_lines.len() && t2 < to_lines.len() && from_lines[f2] == to_lines[t2] {
-        if is_boundary(from_lines[f2]) || is_boundary(to_lines[t2]) {
+    while f2 < from_lines.len() && t2 < to_lines.len() && from_lines[f2].as_ref() == to_lines[t2].as_ref() {
+        if from_mask[f2] || to_mask[t2] {
             break;
         }
         f2 += 1;
@@ -29,8 +129,8 @@ pub fn extend_structural_block(
     }
 
     // Extend backward
-    while f1 > 0 && t1 > 0 && from_lines[f1 - 1] == to_lines[t1 - 1] {
-        if is_boundary(from_lines[f1 - 1]) || is_boundary(to_lines[t1 - 1]) {
+    while f1 > 0 && t1 > 0 && from_lines[f1 - 1].as_ref() == to_lines[t1 - 1].as_ref() {
+        if from_mask[f1 - 1] || to_mask[t1 - 1] {
             break;
         }
         f1 -= 1;
@@ -40,3 +140,58 @@ pub fn extend_structural_block(
     (f1, f2, t1, t2)
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flagged(b: Boundaries, src: &str) -> Vec<usize> {
+        let lines: Vec<&str> = src.lines().collect();
+        b.mask(&lines)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, m)| *m)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    #[test]
+    fn test_selected_by_extension() {
+        assert_eq!(Boundaries::from_path("src/a.py"), Boundaries::Python);
+        assert_eq!(Boundaries::from_path("HEAD:src/lib.rs"), Boundaries::Rust);
+        assert_eq!(Boundaries::from_path("web/app.tsx"), Boundaries::TypeScript);
+        assert_eq!(Boundaries::from_path("main.go"), Boundaries::Go);
+        assert_eq!(Boundaries::from_path("paper.tex"), Boundaries::Latex);
+        assert_eq!(Boundaries::from_path("Main.java"), Boundaries::Braces);
+    }
+
+    #[test]
+    fn test_rust_items() {
+        let src = "#[derive(Debug)]\npub struct A;\nimpl A {\n    pub(crate) fn f() {\n        let x = 1;\n    }\n}\n";
+        assert_eq!(flagged(Boundaries::Rust, src), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_typescript_and_latex() {
+        let ts = "export function f() {\n  return 1;\n}\nclass B {}\n";
+        assert_eq!(flagged(Boundaries::TypeScript, ts), vec![0, 3]);
+
+        let tex = "\\section{Intro}\nText.\n\n\\subsection{More}\n";
+        assert_eq!(flagged(Boundaries::Latex, tex), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_brace_depth_fallback() {
+        let java = "class A {\n  void f() {\n\n    int x = 1;\n  }\n}\n\nclass B {\n}\n";
+        // only top-level openers and blank lines between them
+        assert_eq!(flagged(Boundaries::Braces, java), vec![0, 6, 7]);
+    }
+
+    #[test]
+    fn test_blocks_stop_at_boundaries() {
+        let from = vec!["fn a() {", "    let x = 1;", "    let y = 2;", "}", "fn b() {"];
+        let to = from.clone();
+        let mask = Boundaries::Rust.mask(&from);
+        assert_eq!(extend_structural_block(&from, &to, &mask, &mask, 1, 1), (1, 4, 1, 4));
+    }
+}
@@ -0,0 +1,214 @@
+use super::model::FileView;
+
+/// Which pane the arrow keys move in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Files,
+    Diff,
+}
+
+/// Everything a key can do; the terminal loop maps keys onto these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    ToggleFocus,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    NextFile,
+    PrevFile,
+    NextHunk,
+    PrevHunk,
+    NextMove,
+    PrevMove,
+    /// Follow the move link on the cursor row
+    Jump,
+    /// Return to where the last jump started
+    Back,
+}
+
+/// Browser state: the selected file, the cursor row in its diff and the
+/// first row on screen.
+#[derive(Debug)]
+pub struct App {
+    pub files: Vec<FileView>,
+    pub selected: usize,
+    pub cursor: usize,
+    pub scroll: usize,
+    pub focus: Focus,
+    /// Rows the diff pane showed last time it was drawn
+    pub page: usize,
+    pub quit: bool,
+    /// `(file, row)` before each jump
+    history: Vec<(usize, usize)>,
+}
+
+/// First entry of `stops` after `cursor` (or the last one before it when
+/// going back).
+fn next_stop(stops: &[usize], cursor: usize, forward: bool) -> Option<usize> {
+    if forward {
+        stops.iter().copied().find(|&s| s > cursor)
+    } else {
+        stops.iter().copied().rev().find(|&s| s < cursor)
+    }
+}
+
+impl App {
+    pub fn new(files: Vec<FileView>) -> Self {
+        App { files, selected: 0, cursor: 0, scroll: 0, focus: Focus::Files, page: 20, quit: false, history: Vec::new() }
+    }
+
+    pub fn file(&self) -> Option<&FileView> {
+        self.files.get(self.selected)
+    }
+
+    fn rows(&self) -> usize {
+        self.file().map_or(0, |f| f.rows.len())
+    }
+
+    fn select(&mut self, file: usize) {
+        if file < self.files.len() && file != self.selected {
+            self.selected = file;
+            self.cursor = 0;
+            self.scroll = 0;
+        }
+    }
+
+    fn move_cursor(&mut self, to: usize) {
+        self.cursor = to.min(self.rows().saturating_sub(1));
+    }
+
+    /// Keep the cursor on screen, with the view at a `page` rows high.
+    pub fn follow_cursor(&mut self, page: usize) {
+        self.page = page.max(1);
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.page {
+            self.scroll = self.cursor + 1 - self.page;
+        }
+    }
+
+    /// Jump to the next / previous stop (hunk or move) in this file, then
+    /// to the first / last stop of the neighbouring files.
+    fn stop(&mut self, forward: bool, stops: fn(&FileView) -> &[usize]) {
+        let Some(file) = self.file() else {
+            return;
+        };
+        if let Some(row) = next_stop(stops(file), self.cursor, forward) {
+            self.focus = Focus::Diff;
+            return self.move_cursor(row);
+        }
+
+        let others: Vec<usize> = match forward {
+            true => (self.selected + 1..self.files.len()).collect(),
+            false => (0..self.selected).rev().collect(),
+        };
+        for i in others {
+            let found = stops(&self.files[i]);
+            let row = if forward { found.first() } else { found.last() };
+            if let Some(&row) = row {
+                self.select(i);
+                self.focus = Focus::Diff;
+                return self.move_cursor(row);
+            }
+        }
+    }
+
+    pub fn apply(&mut self, action: Action) {
+        let files = self.focus == Focus::Files;
+        match action {
+            Action::Quit => self.quit = true,
+            Action::ToggleFocus => {
+                self.focus = if files { Focus::Diff } else { Focus::Files };
+            }
+            Action::Up if files => self.select(self.selected.saturating_sub(1)),
+            Action::Down if files => self.select(self.selected + 1),
+            Action::Top if files => self.select(0),
+            Action::Bottom if files => self.select(self.files.len().saturating_sub(1)),
+            Action::Up => self.move_cursor(self.cursor.saturating_sub(1)),
+            Action::Down => self.move_cursor(self.cursor + 1),
+            Action::Top => self.move_cursor(0),
+            Action::Bottom => self.move_cursor(usize::MAX),
+            Action::PageUp => self.move_cursor(self.cursor.saturating_sub(self.page)),
+            Action::PageDown => self.move_cursor(self.cursor + self.page),
+            Action::NextFile => self.select(self.selected + 1),
+            Action::PrevFile => self.select(self.selected.saturating_sub(1)),
+            Action::NextHunk => self.stop(true, |f| &f.hunks),
+            Action::PrevHunk => self.stop(false, |f| &f.hunks),
+            Action::NextMove => self.stop(true, |f| &f.moves),
+            Action::PrevMove => self.stop(false, |f| &f.moves),
+            Action::Jump if files => self.focus = Focus::Diff,
+            Action::Jump => {
+                let target = self.file().and_then(|f| f.rows.get(self.cursor)?.link.as_ref()?.target);
+                if let Some((file, row)) = target {
+                    self.history.push((self.selected, self.cursor));
+                    self.select(file);
+                    self.move_cursor(row);
+                }
+            }
+            Action::Back => {
+                if let Some((file, row)) = self.history.pop() {
+                    self.select(file);
+                    self.focus = Focus::Diff;
+                    self.move_cursor(row);
+                }
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::tui::model::{MoveLink, Row, RowKind};
+    use crate::types::FileStatus;
+
+    fn view(rows: usize, hunks: Vec<usize>, moves: Vec<usize>) -> FileView {
+        let row = Row { kind: RowKind::Context, old_line: None, new_line: None, segments: Vec::new(), link: None };
+        FileView {
+            label: String::new(),
+            status: FileStatus::Modified,
+            from_path: None,
+            to_path: None,
+            moved_blocks: moves.len(),
+            rows: vec![row; rows],
+            hunks,
+            moves,
+        }
+    }
+
+    #[test]
+    fn test_navigation_and_jumps() {
+        let mut source = view(10, vec![0, 6], vec![7]);
+        source.rows[7].link = Some(MoveLink { label: "[MOVED → b.rs:3]".into(), target: Some((2, 4)) });
+        let mut app = App::new(vec![source, view(3, vec![0], vec![]), view(8, vec![0], vec![4])]);
+
+        app.apply(Action::NextHunk);
+        assert_eq!((app.selected, app.cursor, app.focus), (0, 6, Focus::Diff));
+        app.apply(Action::NextHunk);
+        assert_eq!((app.selected, app.cursor), (1, 0));
+        app.apply(Action::PrevHunk);
+        assert_eq!((app.selected, app.cursor), (0, 6));
+
+        app.apply(Action::NextMove);
+        assert_eq!(app.cursor, 7);
+        app.apply(Action::Jump);
+        assert_eq!((app.selected, app.cursor), (2, 4));
+        app.apply(Action::Back);
+        assert_eq!((app.selected, app.cursor), (0, 7));
+
+        app.apply(Action::ToggleFocus);
+        app.apply(Action::Down);
+        assert_eq!((app.selected, app.cursor), (1, 0));
+
+        app.follow_cursor(5);
+        app.apply(Action::ToggleFocus);
+        app.apply(Action::Bottom);
+        app.follow_cursor(2);
+        assert_eq!((app.cursor, app.scroll), (2, 1));
+    }
+}
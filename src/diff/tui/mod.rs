@@ -0,0 +1,64 @@
+pub mod app;
+pub mod model;
+pub mod view;
+
+use std::io;
+
+use atty::Stream;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::DefaultTerminal;
+
+use crate::error::{Error, Result};
+use crate::types::DiffReport;
+
+pub use app::{Action, App};
+
+/// Key bindings (vi-style letters plus arrows and paging keys).
+fn action(key: KeyEvent) -> Option<Action> {
+    let action = match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+        KeyCode::Tab | KeyCode::BackTab => Action::ToggleFocus,
+        KeyCode::Up | KeyCode::Char('k') => Action::Up,
+        KeyCode::Down | KeyCode::Char('j') => Action::Down,
+        KeyCode::PageUp => Action::PageUp,
+        KeyCode::PageDown | KeyCode::Char(' ') => Action::PageDown,
+        KeyCode::Home | KeyCode::Char('g') => Action::Top,
+        KeyCode::End | KeyCode::Char('G') => Action::Bottom,
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(']') => Action::NextFile,
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('[') => Action::PrevFile,
+        KeyCode::Char('n') => Action::NextHunk,
+        KeyCode::Char('N') | KeyCode::Char('p') => Action::PrevHunk,
+        KeyCode::Char('m') => Action::NextMove,
+        KeyCode::Char('M') => Action::PrevMove,
+        KeyCode::Enter => Action::Jump,
+        KeyCode::Backspace | KeyCode::Char('b') => Action::Back,
+        _ => return None,
+    };
+    Some(action)
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| view::draw(frame, app))?;
+        if let Event::Key(key) = event::read()? {
+            if let (KeyEventKind::Press, Some(action)) = (key.kind, action(key)) {
+                app.apply(action);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `ygg diff --tui` — browse `report` full-screen until the user quits.
+pub fn run_tui(report: &DiffReport) -> Result<()> {
+    if !atty::is(Stream::Stdin) || !atty::is(Stream::Stdout) {
+        return Err(Error::InvalidOption("--tui needs an interactive terminal".into()));
+    }
+
+    let mut app = App::new(model::file_views(report));
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result.map_err(|e| Error::io("terminal", e))
+}
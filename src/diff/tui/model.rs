@@ -0,0 +1,218 @@
+use similar::ChangeTag;
+
+use crate::diff::hunks::line_hunks;
+use crate::diff::inline::{Emphasis, Segments};
+use crate::types::{DiffReport, FileDiff, FileStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    /// `@@ -a,b +c,d @@`
+    Hunk,
+    Context,
+    Added,
+    Removed,
+    /// A message instead of a diff (renamed without changes, …)
+    Note,
+}
+
+/// Where a moved line went, or came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveLink {
+    /// `[MOVED → b.rs:12]` on the old side, `[MOVED ← a.rs:3]` on the new
+    pub label: String,
+    /// File and row to jump to, when the other end is shown
+    pub target: Option<(usize, usize)>,
+}
+
+/// One line of the diff pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub kind: RowKind,
+    /// 1-based line numbers on each side
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub segments: Segments,
+    pub link: Option<MoveLink>,
+}
+
+impl Row {
+    fn text(kind: RowKind, text: String) -> Self {
+        Row { kind, old_line: None, new_line: None, segments: vec![(false, text)], link: None }
+    }
+}
+
+/// A changed file as the browser shows it: its rows plus the rows that
+/// start hunks and moved runs, for navigation.
+#[derive(Debug, Clone)]
+pub struct FileView {
+    /// Path, or `from → to` for renames and copies
+    pub label: String,
+    pub status: FileStatus,
+    /// Paths of the two sides, for resolving move links
+    pub from_path: Option<String>,
+    pub to_path: Option<String>,
+    /// Cross-file move blocks leaving or entering this file
+    pub moved_blocks: usize,
+    pub rows: Vec<Row>,
+    pub hunks: Vec<usize>,
+    pub moves: Vec<usize>,
+}
+
+fn file_view(d: &FileDiff, report: &DiffReport) -> FileView {
+    let from = d.from.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+    let to = d.to.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+
+    let mut rows = Vec::new();
+    let mut hunks = Vec::new();
+    for hunk in line_hunks(from, to, &report.hunks, Emphasis::Word) {
+        hunks.push(rows.len());
+        rows.push(Row::text(RowKind::Hunk, hunk.header()));
+        rows.extend(hunk.lines.into_iter().map(|line| Row {
+            kind: match line.tag {
+                ChangeTag::Equal => RowKind::Context,
+                ChangeTag::Delete => RowKind::Removed,
+                ChangeTag::Insert => RowKind::Added,
+            },
+            old_line: line.old_index.map(|i| i + 1),
+            new_line: line.new_index.map(|i| i + 1),
+            segments: line.segments,
+            link: None,
+        }));
+    }
+    if rows.is_empty() {
+        let note = match d.similarity {
+            Some(sim) => format!("{} without content changes (similarity {}%)", d.status.as_str(), sim),
+            None => "no content changes".to_string(),
+        };
+        rows.push(Row::text(RowKind::Note, note));
+    }
+
+    let label = match (d.status, &d.from, &d.to) {
+        (FileStatus::Renamed | FileStatus::Copied, Some(f), Some(t)) => format!("{} → {}", f.path, t.path),
+        _ => d.from.as_ref().or(d.to.as_ref()).map(|f| f.path.clone()).unwrap_or_default(),
+    };
+    let from_path = d.from.as_ref().map(|f| f.path.clone());
+    let to_path = d.to.as_ref().map(|f| f.path.clone());
+    let moved_blocks = report
+        .moves
+        .iter()
+        .flat_map(|g| g.blocks.iter().map(move |b| (g, &b.block)))
+        .filter(|(g, m)| Some(&g.from_file) == from_path.as_ref() || Some(&m.to_file) == to_path.as_ref())
+        .count();
+
+    FileView { label, status: d.status, from_path, to_path, moved_blocks, rows, hunks, moves: Vec::new() }
+}
+
+/// First row of `file` at or after line `line` on one side (the nearest one
+/// shown when the line itself is outside every hunk).
+fn row_at(file: &FileView, line: usize, new_side: bool) -> usize {
+    let side = |r: &Row| if new_side { r.new_line } else { r.old_line };
+    file.rows
+        .iter()
+        .position(|r| side(r).is_some_and(|l| l >= line))
+        .unwrap_or(0)
+}
+
+/// Changed files of `report` in pairing order, with `[MOVED → file:line]`
+/// links on the lines of every cross-file move.
+pub fn file_views(report: &DiffReport) -> Vec<FileView> {
+    let mut files: Vec<FileView> = report
+        .files
+        .iter()
+        .filter(|d| d.status != FileStatus::Unchanged)
+        .map(|d| file_view(d, report))
+        .collect();
+
+    let find = |files: &[FileView], path: &str, new_side: bool| {
+        files.iter().position(|f| if new_side { f.to_path.as_deref() } else { f.from_path.as_deref() } == Some(path))
+    };
+
+    for group in &report.moves {
+        for bwv in &group.blocks {
+            let m = &bwv.block;
+            let kind = if bwv.is_addition { "ADDED" } else { "MOVED" };
+            let source = find(&files, &group.from_file, false);
+            let dest = find(&files, &m.to_file, true);
+
+            // lines of the block, 1-based, on each side
+            let pairs: Vec<(usize, usize)> = (m.from_range.0..m.from_range.1)
+                .zip(m.to_range.0..m.to_range.1)
+                .map(|(a, b)| (a + 1, b + 1))
+                .collect();
+
+            for &(old, new) in &pairs {
+                let to_row = dest.map(|d| (d, row_at(&files[d], new, true)));
+                let from_row = source.map(|s| (s, row_at(&files[s], old, false)));
+
+                if let Some(s) = source {
+                    let link = MoveLink { label: format!("[{} → {}:{}]", kind, m.to_file, new), target: to_row };
+                    if let Some(row) = files[s].rows.iter_mut().find(|r| r.kind == RowKind::Removed && r.old_line == Some(old)) {
+                        row.link = Some(link);
+                    }
+                }
+                if let Some(d) = dest {
+                    let link = MoveLink { label: format!("[{} ← {}:{}]", kind, group.from_file, old), target: from_row };
+                    if let Some(row) = files[d].rows.iter_mut().find(|r| r.kind == RowKind::Added && r.new_line == Some(new)) {
+                        row.link = Some(link);
+                    }
+                }
+            }
+        }
+    }
+
+    for file in &mut files {
+        file.moves = (0..file.rows.len())
+            .filter(|&i| file.rows[i].link.is_some() && (i == 0 || file.rows[i - 1].link.is_none()))
+            .collect();
+    }
+    files
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BlockMatch, BlockWithVote, GroupedMatches, SourceFile};
+
+    fn source(path: &str, content: &str) -> Option<SourceFile> {
+        Some(SourceFile { rel: path.into(), path: path.into(), content: content.into() })
+    }
+
+    #[test]
+    fn test_moved_lines_link_both_ends() {
+        let old_a = "fn keep() {}\n\nfn helper() {\n    1\n}\n";
+        let report = DiffReport {
+            files: vec![
+                FileDiff { status: FileStatus::Modified, from: source("a.rs", old_a), to: source("a.rs", "fn keep() {}\n"), similarity: None },
+                FileDiff { status: FileStatus::Added, from: None, to: source("b.rs", "use x;\nfn helper() {\n    1\n}\n"), similarity: None },
+            ],
+            moves: vec![GroupedMatches {
+                from_file: "a.rs".into(),
+                to_file: "b.rs".into(),
+                blocks: vec![BlockWithVote {
+                    block: BlockMatch { from_file: "a.rs".into(), from_range: (2, 5), to_file: "b.rs".into(), to_range: (1, 4), similarity: 100 },
+                    is_addition: false,
+                }],
+                from_content: old_a.into(),
+            }],
+            ..DiffReport::default()
+        };
+
+        let files = file_views(&report);
+        assert_eq!(files.len(), 2);
+        assert_eq!((files[0].moved_blocks, files[1].moved_blocks), (1, 1));
+        assert_eq!(files[0].hunks, [0]);
+
+        let a = &files[0];
+        let first = a.moves[0];
+        assert_eq!(a.rows[first].old_line, Some(3));
+        let link = a.rows[first].link.as_ref().unwrap();
+        assert_eq!(link.label, "[MOVED → b.rs:2]");
+
+        let (file, row) = link.target.unwrap();
+        assert_eq!(file, 1);
+        assert_eq!(files[1].rows[row].new_line, Some(2));
+        assert_eq!(files[1].rows[row].link.as_ref().unwrap().label, "[MOVED ← a.rs:3]");
+        assert_eq!(files[1].moves, [row]);
+    }
+}
@@ -0,0 +1,154 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use crate::types::FileStatus;
+
+use super::app::{App, Focus};
+use super::model::{Row, RowKind};
+
+const HELP: &str =
+    " q quit · tab switch pane · j/k move · n/N hunk · m/M moved block · enter follow move · backspace back · ←/→ file";
+
+fn status_letter(status: FileStatus) -> (&'static str, Color) {
+    match status {
+        FileStatus::Added => ("A", Color::Green),
+        FileStatus::Removed => ("D", Color::Red),
+        FileStatus::Modified => ("M", Color::Yellow),
+        FileStatus::Renamed => ("R", Color::Cyan),
+        FileStatus::Copied => ("C", Color::Cyan),
+        FileStatus::Unchanged => (" ", Color::Reset),
+    }
+}
+
+fn pane(title: String, focused: bool) -> Block<'static> {
+    let border = if focused { Style::new().fg(Color::Cyan) } else { Style::new().fg(Color::DarkGray) };
+    Block::bordered().title(title).border_style(border)
+}
+
+/// One diff row: line numbers, sign, text (emphasised pieces reversed) and
+/// the move annotation.
+fn row_line(row: &Row) -> Line<'static> {
+    let number = |n: Option<usize>| n.map_or_else(|| " ".repeat(5), |n| format!("{:>5}", n));
+    let (sign, style) = match row.kind {
+        RowKind::Hunk => ("", Style::new().fg(Color::Cyan)),
+        RowKind::Note => ("", Style::new().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)),
+        RowKind::Context => (" ", Style::new()),
+        RowKind::Added => ("+", Style::new().fg(Color::Green)),
+        RowKind::Removed => ("-", Style::new().fg(Color::Red)),
+    };
+
+    let mut spans = Vec::new();
+    if !matches!(row.kind, RowKind::Hunk | RowKind::Note) {
+        let gutter = format!("{} {} ", number(row.old_line), number(row.new_line));
+        spans.push(Span::styled(gutter, Style::new().fg(Color::DarkGray)));
+    }
+    spans.push(Span::styled(sign, style));
+    for (emphasised, text) in &row.segments {
+        let style = if *emphasised { style.add_modifier(Modifier::REVERSED) } else { style };
+        spans.push(Span::styled(text.replace('\t', "    "), style));
+    }
+    if let Some(link) = &row.link {
+        spans.push(Span::styled(format!("  {}", link.label), Style::new().fg(Color::Yellow)));
+    }
+    Line::from(spans)
+}
+
+fn draw_files(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .files
+        .iter()
+        .map(|f| {
+            let (letter, color) = status_letter(f.status);
+            let mut spans = vec![Span::styled(letter, Style::new().fg(color)), Span::raw(" "), Span::raw(f.label.clone())];
+            if f.moved_blocks > 0 {
+                spans.push(Span::styled(format!(" ⇄{}", f.moved_blocks), Style::new().fg(Color::Yellow)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = format!(" Files ({}) ", app.files.len());
+    let list = List::new(items)
+        .block(pane(title, app.focus == Focus::Files))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_diff(frame: &mut Frame, app: &mut App, area: Rect) {
+    app.follow_cursor(area.height.saturating_sub(2) as usize);
+    let focused = app.focus == Focus::Diff;
+    let Some(file) = app.file() else {
+        frame.render_widget(Paragraph::new("No differences").block(pane(String::new(), focused)), area);
+        return;
+    };
+
+    let hunk = file.hunks.iter().filter(|&&h| h <= app.cursor).count();
+    let title = format!(" {} — hunk {}/{} ", file.label, hunk, file.hunks.len());
+    let lines: Vec<Line> = file
+        .rows
+        .iter()
+        .enumerate()
+        .skip(app.scroll)
+        .take(app.page)
+        .map(|(i, row)| {
+            let line = row_line(row);
+            if i == app.cursor && focused {
+                line.patch_style(Style::new().bg(Color::DarkGray))
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines).block(pane(title, focused)), area);
+}
+
+/// File list on the left, the selected file's diff on the right, key help
+/// at the bottom.
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [main, help] = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+    let [files, diff] = Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(main);
+
+    draw_files(frame, app, files);
+    draw_diff(frame, app, diff);
+    frame.render_widget(Paragraph::new(HELP).style(Style::new().fg(Color::DarkGray)), help);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::tui::model::file_views;
+    use crate::types::{DiffReport, FileDiff, SourceFile};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn test_draws_file_list_and_diff() {
+        let file = |content: &str| Some(SourceFile { rel: "a.py".into(), path: "a.py".into(), content: content.into() });
+        let report = DiffReport {
+            files: vec![FileDiff { status: FileStatus::Modified, from: file("x = 1\n"), to: file("x = 2\n"), similarity: None }],
+            ..DiffReport::default()
+        };
+        let mut app = App::new(file_views(&report));
+        let mut terminal = Terminal::new(TestBackend::new(100, 8)).unwrap();
+        terminal.draw(|frame| draw(frame, &mut app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen: Vec<String> = (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect();
+        let screen = screen.join("\n");
+
+        assert!(screen.contains(" Files (1) "));
+        assert!(screen.contains("M a.py"));
+        assert!(screen.contains(" a.py — hunk 1/1 "));
+        assert!(screen.contains("@@ -1,1 +1,1 @@"));
+        assert!(screen.contains("    1       -x = 1"));
+        assert!(screen.contains("          1 +x = 2"));
+    }
+}
@@ -0,0 +1,56 @@
+use std::fmt;
+use std::io;
+
+/// Errors surfaced by the library API.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a path failed.
+    Io { path: String, source: io::Error },
+    /// `--dir` / `root` does not exist or is not a directory.
+    RootNotFound(String),
+    /// The sniff entry file is missing or lies outside the root.
+    SniffEntry { entry: String, root: String },
+    /// Snapshot store lookups (unknown or ambiguous id, corrupt manifest).
+    Store(String),
+    /// The file watcher could not be started.
+    Watch(String),
+    /// A git command (for `ygg diff --rev`) failed.
+    Git(String),
+    /// An option combination that cannot be honoured.
+    InvalidOption(String),
+}
+
+impl Error {
+    pub fn io(path: impl Into<String>, source: io::Error) -> Self {
+        Error::Io { path: path.into(), source }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "{}: {}", path, source),
+            Error::RootNotFound(root) => write!(f, "root directory '{}' not found", root),
+            Error::SniffEntry { entry, root } => write!(
+                f,
+                "Yggdrasil could not trace the branches of '{}' under root '{}'. Verify paths and --dir.",
+                entry, root
+            ),
+            Error::Store(msg) => write!(f, "snapshot store: {}", msg),
+            Error::Watch(msg) => write!(f, "watch: {}", msg),
+            Error::Git(msg) => write!(f, "{}", msg),
+            Error::InvalidOption(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -1,7 +1,8 @@
-use std::fs;
 use std::io::Write;
 use colored::*;
 
+use crate::codex::integrity::short_hash;
+use crate::error::{Error, Result};
 use crate::types::FileEntry;
 
 use super::traits::OutputFormatter;
@@ -37,7 +38,7 @@ impl OutputFormatter for CliFormatter {
         .unwrap();
     }
 
-    fn print_index(&self, files: &Vec<FileEntry>, out: &mut dyn Write) {
+    fn print_index(&self, files: &[FileEntry], out: &mut dyn Write) {
 
         let path_width = files
         .iter()
@@ -76,7 +77,7 @@ impl OutputFormatter for CliFormatter {
         // header
         writeln!(
             out,
-            "{:<path_w$} : {:>line_w$} | {:>word_w$} | {:>token_w$}",
+            "{:<path_w$} : {:>line_w$} | {:>word_w$} | {:>token_w$} | hash",
             "path",
             "lines",
             "words",
@@ -98,12 +99,13 @@ impl OutputFormatter for CliFormatter {
             if self.colored {
                 writeln!(
                     out,
-                    "{} {:<path_w$} : {:>line_w$} | {:>word_w$} | {:>token_w$}",
+                    "{} {:<path_w$} : {:>line_w$} | {:>word_w$} | {:>token_w$} | {}",
                     "📄".truecolor(255,255,0),
                     entry.path.truecolor(0,255,255),
                     entry.line_count,
                     entry.word_count,
                     entry.token_est,
+                    short_hash(&entry.hash).dimmed(),
                     path_w = path_width,
                     line_w = line_width,
                     word_w = word_width,
@@ -112,11 +114,12 @@ impl OutputFormatter for CliFormatter {
             } else {
                 writeln!(
                     out,
-                    "📄 {:<path_w$} : {:>line_w$} | {:>word_w$} | {:>token_w$}",
+                    "📄 {:<path_w$} : {:>line_w$} | {:>word_w$} | {:>token_w$} | {}",
                     entry.path,
                     entry.line_count,
                     entry.word_count,
                     entry.token_est,
+                    short_hash(&entry.hash),
                     path_w = path_width,
                     line_w = line_width,
                     word_w = word_width,
@@ -141,7 +144,7 @@ impl OutputFormatter for CliFormatter {
         }
     }
 
-    fn print_contents(&self, files: &Vec<FileEntry>, out: &mut dyn Write) {
+    fn print_contents(&self, files: &[FileEntry], out: &mut dyn Write) -> Result<()> {
         for entry in files {
             if self.colored {
                 writeln!(
@@ -155,10 +158,8 @@ impl OutputFormatter for CliFormatter {
                 writeln!(out, "<<< FILE START: {} >>>", entry.path).unwrap();
             }
 
-            match fs::read_to_string(&entry.path) {
-                Ok(content) => write!(out, "{}", content).unwrap(),
-                Err(_) => writeln!(out, "❌ Error reading file").unwrap(),
-            };
+            let content = entry.read_contents().map_err(|e| Error::io(&entry.path, e))?;
+            write!(out, "{}", content).unwrap();
 
             if self.colored {
                 writeln!(
@@ -174,6 +175,7 @@ impl OutputFormatter for CliFormatter {
 
             writeln!(out).unwrap();
         }
+        Ok(())
     }
 }
 
@@ -190,12 +192,18 @@ mod tests {
                 line_count: 10,
                 word_count: 20,
                 token_est: 27,
+                hash: "9f2c1a7be04d5e6f".into(),
+                blob: None,
+                text: None,
             },
             FileEntry {
                 path: "src/formatter.rs".into(),
                 line_count: 5,
                 word_count: 12,
                 token_est: 16,
+                hash: "03ab88c4d1e2f790".into(),
+                blob: None,
+                text: None,
             }
         ]
     }
@@ -211,6 +219,7 @@ mod tests {
         assert!(out.contains("src/main.rs"));
         assert!(out.contains("src/formatter.rs"));
         assert!(out.contains("lines")); // column header is present
+        assert!(out.contains("9f2c1a7be04d")); // short content hash
     }
 
     #[test]
@@ -226,12 +235,26 @@ mod tests {
 
     #[test]
     fn test_file_contents_marker() {
+        let mut files = sample_files();
+        for f in &mut files {
+            f.text = Some("fn main() {}\n".into());
+        }
+
         let mut buf = Vec::new();
         let fmt = CliFormatter { colored: false };
-        fmt.print_contents(&sample_files(), &mut buf);
+        fmt.print_contents(&files, &mut buf).unwrap();
         let out = String::from_utf8(buf).unwrap();
 
-        assert!(out.contains("<<< FILE START: src/main.rs >>>"));
-        assert!(out.contains("<<< FILE END: src/main.rs >>>"));
+        assert!(out.contains("<<< FILE START: src/main.rs >>>\nfn main() {}\n<<< FILE END: src/main.rs >>>"));
+    }
+
+    #[test]
+    fn test_unreadable_file_is_an_error() {
+        let mut files = sample_files();
+        files[0].path = "no/such/file.rs".into();
+
+        let mut buf = Vec::new();
+        let err = CliFormatter { colored: false }.print_contents(&files, &mut buf).unwrap_err();
+        assert!(matches!(err, Error::Io { path, .. } if path == "no/such/file.rs"));
     }
 }
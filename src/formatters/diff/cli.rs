@@ -1,16 +1,25 @@
-use std::fs;
 use std::io::Write;
 use colored::*;
 
-use crate::types::GroupedMatches;
+use crate::diff::options::HunkOptions;
+use crate::types::{FileDiff, GroupedMatches};
 use crate::formatters::traits::DiffFormatter;
+use crate::diff::inline::{diff_file_contents, Emphasis};
+use super::{block_tag, pair_label, write_file_changes};
 
 pub struct DiffCliFormatter {
     pub colored: bool,
+    pub emphasis: Emphasis,
     pub align_tags: bool,
 }
 
 impl DiffFormatter for DiffCliFormatter {
+    fn print_files(&self, files: &[FileDiff], hunks: &HunkOptions, out: &mut dyn Write) {
+        write_file_changes(files, out, |f, t, out| {
+            diff_file_contents(&pair_label(f, t), &f.content, &t.content, self.colored, self.emphasis, hunks, out)
+        });
+    }
+
     fn print_preamble(&self, out: &mut dyn Write) {
         if self.colored {
             writeln!(out, "{}\n", "📦 Cross-file Diff Report".bright_magenta().bold()).unwrap();
@@ -19,7 +28,7 @@ impl DiffFormatter for DiffCliFormatter {
         }
     }
 
-    fn print_index(&self, groups: &Vec<GroupedMatches>, out: &mut dyn Write) {
+    fn print_index(&self, groups: &[GroupedMatches], out: &mut dyn Write) {
         writeln!(out, "📄 File Pairs").unwrap();
 
         for g in groups {
@@ -39,7 +48,7 @@ impl DiffFormatter for DiffCliFormatter {
         writeln!(out).unwrap();
     }
 
-    fn print_contents(&self, groups: &Vec<GroupedMatches>, out: &mut dyn Write) {
+    fn print_contents(&self, groups: &[GroupedMatches], out: &mut dyn Write) {
         for g in groups {
             if self.colored {
                 writeln!(
@@ -53,66 +62,64 @@ impl DiffFormatter for DiffCliFormatter {
                 writeln!(out, "{} → {}", g.from_file, g.to_file).unwrap();
             }
 
-            if let Ok(src) = fs::read_to_string(&g.from_file) {
-                let lines: Vec<&str> = src.lines().collect();
-                let max_len = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+            let lines: Vec<&str> = g.from_content.lines().collect();
+            let max_len = lines.iter().map(|l| l.len()).max().unwrap_or(0);
 
-                for (lineno, line) in lines.iter().enumerate() {
-                    let mut tag = String::new();
+            for (lineno, line) in lines.iter().enumerate() {
+                let mut tag = String::new();
 
-                    for bwv in &g.blocks {
-                        let m = &bwv.block;
-                        let status = if bwv.is_addition { "[ADDED]" } else { "[MOVED]" };
+                for bwv in &g.blocks {
+                    let m = &bwv.block;
+                    let status = block_tag(bwv);
 
-                        if lineno >= m.from_range.0 && lineno < m.from_range.1 {
-                            tag = format!(
-                                " {} ({}–{} → {}–{})",
-                                status,
-                                m.from_range.0, m.from_range.1,
-                                m.to_file, m.to_range.0
-                            );
-                            break;
-                        }
+                    if lineno >= m.from_range.0 && lineno < m.from_range.1 {
+                        tag = format!(
+                            " {} ({}–{} → {}–{})",
+                            status,
+                            m.from_range.0, m.from_range.1,
+                            m.to_file, m.to_range.0
+                        );
+                        break;
                     }
+                }
 
-                    if self.align_tags {
-                        if self.colored {
-                            writeln!(
-                                out,
-                                "{:>4} {:<width$}{}",
-                                lineno + 1,
-                                line,
-                                tag.bright_yellow(),
-                                width = max_len + 1
-                            ).unwrap();
-                        } else {
-                            writeln!(
-                                out,
-                                "{:>4} {:<width$}{}",
-                                lineno + 1,
-                                line,
-                                tag,
-                                width = max_len + 1
-                            ).unwrap();
-                        }
+                if self.align_tags {
+                    if self.colored {
+                        writeln!(
+                            out,
+                            "{:>4} {:<width$}{}",
+                            lineno + 1,
+                            line,
+                            tag.bright_yellow(),
+                            width = max_len + 1
+                        ).unwrap();
+                    } else {
+                        writeln!(
+                            out,
+                            "{:>4} {:<width$}{}",
+                            lineno + 1,
+                            line,
+                            tag,
+                            width = max_len + 1
+                        ).unwrap();
+                    }
+                } else {
+                    if self.colored {
+                        writeln!(
+                            out,
+                            "{:>4} {}{}",
+                            lineno + 1,
+                            line,
+                            tag.bright_yellow()
+                        ).unwrap();
                     } else {
-                        if self.colored {
-                            writeln!(
-                                out,
-                                "{:>4} {}{}",
-                                lineno + 1,
-                                line,
-                                tag.bright_yellow()
-                            ).unwrap();
-                        } else {
-                            writeln!(
-                                out,
-                                "{:>4} {}{}",
-                                lineno + 1,
-                                line,
-                                tag
-                            ).unwrap();
-                        }
+                        writeln!(
+                            out,
+                            "{:>4} {}{}",
+                            lineno + 1,
+                            line,
+                            tag
+                        ).unwrap();
                     }
                 }
             }
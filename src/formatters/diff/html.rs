@@ -0,0 +1,186 @@
+use std::io::Write;
+
+use similar::ChangeTag;
+
+use crate::diff::hunks::line_hunks;
+use crate::diff::inline::Emphasis;
+use crate::diff::options::HunkOptions;
+use crate::formatters::traits::DiffFormatter;
+use crate::types::{DiffReport, FileDiff, FileStatus, GroupedMatches};
+use super::{block_tag, emphasised_html, escape_html, pair_label};
+
+/// Self-contained stylesheet; the page has no external assets.
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2em; color: #1f2328; }
+h2 { font-size: 1em; font-family: ui-monospace, monospace; margin: 1.5em 0 0.5em; }
+table { border-collapse: collapse; width: 100%; font-family: ui-monospace, monospace; font-size: 13px; }
+td { padding: 0 0.5em; white-space: pre-wrap; vertical-align: top; }
+td.num { color: #8c959f; text-align: right; width: 3em; user-select: none; }
+tr.hunk td { background: #ddf4ff; color: #57606a; }
+tr.del td.text { background: #ffebe9; }
+tr.ins td.text { background: #e6ffec; }
+del { background: #ff818266; text-decoration: none; }
+ins { background: #abf2bc; text-decoration: none; }
+.status { color: #57606a; }
+tr.moved td.text { background: #fff8c5; }
+td.tag { color: #9a6700; white-space: nowrap; }
+";
+
+/// Standalone HTML page: one table of hunks per file, changed words marked
+/// with `<del>` / `<ins>`, then the cross-file moves.
+pub struct DiffHtmlFormatter {
+    pub emphasis: Emphasis,
+}
+
+impl DiffHtmlFormatter {
+    fn print_pair(&self, header: &str, d: &FileDiff, hunks: &HunkOptions, out: &mut dyn Write) {
+        writeln!(out, "<h2>{}</h2>", escape_html(header)).unwrap();
+
+        let (Some(f), Some(t)) = (&d.from, &d.to) else {
+            return;
+        };
+        let hunks = line_hunks(&f.content, &t.content, hunks, self.emphasis);
+        if hunks.is_empty() {
+            return;
+        }
+
+        writeln!(out, "<table>").unwrap();
+        for hunk in hunks {
+            writeln!(out, "<tr class=\"hunk\"><td colspan=\"3\">{}</td></tr>", escape_html(&hunk.header())).unwrap();
+
+            for line in hunk.lines {
+                let number = |n: Option<usize>| n.map(|n| (n + 1).to_string()).unwrap_or_default();
+                let (class, text) = match line.tag {
+                    ChangeTag::Delete => ("del", emphasised_html(&line.segments, "del")),
+                    ChangeTag::Insert => ("ins", emphasised_html(&line.segments, "ins")),
+                    ChangeTag::Equal => ("eq", escape_html(&line.text())),
+                };
+                writeln!(
+                    out,
+                    "<tr class=\"{}\"><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"text\">{}</td></tr>",
+                    class,
+                    number(line.old_index),
+                    number(line.new_index),
+                    text,
+                ).unwrap();
+            }
+        }
+        writeln!(out, "</table>").unwrap();
+    }
+}
+
+impl DiffFormatter for DiffHtmlFormatter {
+    fn print_files(&self, files: &[FileDiff], hunks: &HunkOptions, out: &mut dyn Write) {
+        for d in files {
+            match (d.status, &d.from, &d.to) {
+                (FileStatus::Removed, Some(f), _) => {
+                    writeln!(out, "<h2><span class=\"status\">removed</span> {}</h2>", escape_html(&f.path)).unwrap();
+                }
+                (FileStatus::Added, _, Some(t)) => {
+                    writeln!(out, "<h2><span class=\"status\">added</span> {}</h2>", escape_html(&t.path)).unwrap();
+                }
+                (FileStatus::Renamed | FileStatus::Copied, Some(f), Some(t)) => {
+                    let letter = if d.status == FileStatus::Copied { 'C' } else { 'R' };
+                    let header = format!("{}{:03} {} → {}", letter, d.similarity.unwrap_or(0), f.path, t.path);
+                    self.print_pair(&header, d, hunks, out);
+                }
+                (FileStatus::Modified, Some(f), Some(t)) => {
+                    self.print_pair(&pair_label(f, t), d, hunks, out);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn print_preamble(&self, out: &mut dyn Write) {
+        writeln!(out, "<h1>📦 Cross-file Diff Report</h1>").unwrap();
+    }
+
+    fn print_index(&self, groups: &[GroupedMatches], out: &mut dyn Write) {
+        writeln!(out, "<ul>").unwrap();
+        for g in groups {
+            writeln!(out, "<li>{} → {}</li>", escape_html(&g.from_file), escape_html(&g.to_file)).unwrap();
+        }
+        writeln!(out, "</ul>").unwrap();
+    }
+
+    /// The source of every pair, moved lines highlighted and tagged on the
+    /// first line of their block.
+    fn print_contents(&self, groups: &[GroupedMatches], out: &mut dyn Write) {
+        for g in groups {
+            writeln!(out, "<h2>{} → {}</h2>", escape_html(&g.from_file), escape_html(&g.to_file)).unwrap();
+            writeln!(out, "<table>").unwrap();
+
+            for (lineno, line) in g.from_content.lines().enumerate() {
+                let block = g
+                    .blocks
+                    .iter()
+                    .find(|b| lineno >= b.block.from_range.0 && lineno < b.block.from_range.1);
+
+                let tag = block
+                    .filter(|b| b.block.from_range.0 == lineno)
+                    .map(|b| format!("{} → {}:{}", block_tag(b), b.block.to_file, b.block.to_range.0 + 1))
+                    .unwrap_or_default();
+
+                writeln!(
+                    out,
+                    "<tr{}><td class=\"num\">{}</td><td class=\"text\">{}</td><td class=\"tag\">{}</td></tr>",
+                    if block.is_some() { " class=\"moved\"" } else { "" },
+                    lineno + 1,
+                    escape_html(line),
+                    escape_html(&tag),
+                ).unwrap();
+            }
+
+            writeln!(out, "</table>").unwrap();
+        }
+    }
+
+    fn render(&self, report: &DiffReport, out: &mut dyn Write) {
+        writeln!(out, "<!DOCTYPE html>").unwrap();
+        writeln!(out, "<html>\n<head>\n<meta charset=\"utf-8\">\n<title>ygg diff</title>").unwrap();
+        writeln!(out, "<style>\n{}</style>\n</head>\n<body>", STYLE).unwrap();
+
+        self.print_files(&report.files, &report.hunks, out);
+
+        if !report.moves.is_empty() {
+            self.print_preamble(out);
+            self.print_index(&report.moves, out);
+            self.print_contents(&report.moves, out);
+        }
+
+        writeln!(out, "</body>\n</html>").unwrap();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SourceFile;
+
+    #[test]
+    fn test_html_marks_changed_words_and_escapes() {
+        let file = |content: &str| SourceFile { rel: "a.rs".into(), path: "a.rs".into(), content: content.into() };
+        let report = DiffReport {
+            files: vec![FileDiff {
+                status: FileStatus::Modified,
+                from: Some(file("if a < b { keep(); }\nlet total = price + tax;\n")),
+                to: Some(file("if a < b { keep(); }\nlet total = cost + tax;\n")),
+                similarity: None,
+            }],
+            moves: Vec::new(),
+            hunks: HunkOptions::default(),
+        };
+
+        let mut buf = Vec::new();
+        DiffHtmlFormatter { emphasis: Emphasis::Word }.render(&report, &mut buf);
+        let out = String::from_utf8(buf).unwrap();
+
+        assert!(out.starts_with("<!DOCTYPE html>"));
+        assert!(out.contains("if a &lt; b { keep(); }"));
+        assert!(out.contains("let total = <del>price</del> + tax;"));
+        assert!(out.contains("let total = <ins>cost</ins> + tax;"));
+        assert!(out.trim_end().ends_with("</html>"));
+    }
+}
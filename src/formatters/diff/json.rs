@@ -0,0 +1,181 @@
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::diff::hunks::compute_hunks;
+use crate::formatters::traits::DiffFormatter;
+use crate::types::{DiffReport, FileDiff, FileStatus, GroupedMatches, Hunk};
+
+/// Bumped whenever a field is renamed or removed (additions keep the version).
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Machine-readable diff report (schema documented in the README).
+pub struct DiffJsonFormatter;
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    version: u32,
+    summary: JsonSummary,
+    files: Vec<JsonFile<'a>>,
+    moves: Vec<JsonMoveGroup<'a>>,
+}
+
+#[derive(Serialize, Default)]
+struct JsonSummary {
+    added: usize,
+    removed: usize,
+    modified: usize,
+    renamed: usize,
+    copied: usize,
+    unchanged: usize,
+}
+
+#[derive(Serialize)]
+struct JsonFile<'a> {
+    status: &'static str,
+    /// Root-relative path (new side when both exist)
+    path: &'a str,
+    old_path: Option<&'a str>,
+    new_path: Option<&'a str>,
+    /// 0–100, renames and copies only
+    similarity: Option<u8>,
+    hunks: Vec<JsonHunk<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonHunk<'a> {
+    old_start: usize,
+    old_lines: usize,
+    new_start: usize,
+    new_lines: usize,
+    lines: Vec<JsonLine<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    op: char,
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonMoveGroup<'a> {
+    from_file: &'a str,
+    to_file: &'a str,
+    blocks: Vec<JsonBlock>,
+}
+
+#[derive(Serialize)]
+struct JsonBlock {
+    /// "moved", or "added" for a repeat of a block already reported
+    kind: &'static str,
+    /// 0–100 character-level similarity (below 100 only with a fuzzy
+    /// --move-match mode)
+    similarity: u8,
+    from: JsonRange,
+    to: JsonRange,
+}
+
+/// 1-based, inclusive line range.
+#[derive(Serialize)]
+struct JsonRange {
+    start: usize,
+    end: usize,
+}
+
+impl JsonRange {
+    fn from_half_open((start, end): (usize, usize)) -> Self {
+        Self { start: start + 1, end }
+    }
+}
+
+fn json_hunks(hunks: &[Hunk]) -> Vec<JsonHunk<'_>> {
+    hunks
+        .iter()
+        .map(|h| JsonHunk {
+            old_start: h.old_start,
+            old_lines: h.old_lines,
+            new_start: h.new_start,
+            new_lines: h.new_lines,
+            lines: h.lines.iter().map(|l| JsonLine { op: l.op, text: &l.text }).collect(),
+        })
+        .collect()
+}
+
+fn summarize(files: &[FileDiff]) -> JsonSummary {
+    let mut s = JsonSummary::default();
+    for f in files {
+        match f.status {
+            FileStatus::Added => s.added += 1,
+            FileStatus::Removed => s.removed += 1,
+            FileStatus::Modified => s.modified += 1,
+            FileStatus::Renamed => s.renamed += 1,
+            FileStatus::Copied => s.copied += 1,
+            FileStatus::Unchanged => s.unchanged += 1,
+        }
+    }
+    s
+}
+
+fn json_moves(groups: &[GroupedMatches]) -> Vec<JsonMoveGroup<'_>> {
+    groups
+        .iter()
+        .map(|g| JsonMoveGroup {
+            from_file: &g.from_file,
+            to_file: &g.to_file,
+            blocks: g
+                .blocks
+                .iter()
+                .map(|b| JsonBlock {
+                    kind: if b.is_addition { "added" } else { "moved" },
+                    similarity: b.block.similarity,
+                    from: JsonRange::from_half_open(b.block.from_range),
+                    to: JsonRange::from_half_open(b.block.to_range),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+impl DiffFormatter for DiffJsonFormatter {
+    // The whole document is emitted by `render`.
+    fn print_preamble(&self, _out: &mut dyn Write) {}
+    fn print_index(&self, _groups: &[GroupedMatches], _out: &mut dyn Write) {}
+    fn print_contents(&self, _groups: &[GroupedMatches], _out: &mut dyn Write) {}
+
+    fn render(&self, report: &DiffReport, out: &mut dyn Write) {
+        let hunks: Vec<Vec<Hunk>> = report
+            .files
+            .iter()
+            .map(|d| {
+                let from = d.from.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+                let to = d.to.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+                compute_hunks(from, to, &report.hunks)
+            })
+            .collect();
+
+        let files = report
+            .files
+            .iter()
+            .zip(&hunks)
+            .filter(|(d, _)| d.status != FileStatus::Unchanged)
+            .map(|(d, h)| JsonFile {
+                status: d.status.as_str(),
+                path: d.rel(),
+                old_path: d.from.as_ref().map(|f| f.path.as_str()),
+                new_path: d.to.as_ref().map(|f| f.path.as_str()),
+                similarity: d.similarity,
+                hunks: json_hunks(h),
+            })
+            .collect();
+
+        let doc = JsonReport {
+            version: JSON_SCHEMA_VERSION,
+            summary: summarize(&report.files),
+            files,
+            moves: json_moves(&report.moves),
+        };
+
+        serde_json::to_writer_pretty(&mut *out, &doc).unwrap();
+        writeln!(out).unwrap();
+    }
+}
@@ -1,62 +1,217 @@
-use std::fs;
 use std::io::Write;
-use crate::types::GroupedMatches;
 
+use crate::diff::hunks::compute_hunks;
+use crate::diff::options::HunkOptions;
+use crate::formatters::markdown::code_lang;
+use crate::types::{DiffReport, FileDiff, FileStatus, GroupedMatches};
+use super::{block_tag, pair_label};
+
+/// Complete Markdown report: status summary, file table, one ```diff fence
+/// per changed file, then the cross-file moves.
 pub struct DiffMarkdownFormatter;
 
+/// A code fence longer than any backtick run in `text`, so content that
+/// contains fences of its own cannot close ours.
+fn fence_for(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+/// `| modified | 3 |` rows for every status that occurs, in a fixed order.
+fn write_summary(files: &[FileDiff], out: &mut dyn Write) {
+    let order = [
+        FileStatus::Added,
+        FileStatus::Removed,
+        FileStatus::Modified,
+        FileStatus::Renamed,
+        FileStatus::Copied,
+        FileStatus::Unchanged,
+    ];
+
+    writeln!(out, "| Status | Files |").unwrap();
+    writeln!(out, "| ------ | ----: |").unwrap();
+    for status in order {
+        let count = files.iter().filter(|d| d.status == status).count();
+        if count > 0 {
+            writeln!(out, "| {} | {} |", status.as_str(), count).unwrap();
+        }
+    }
+    writeln!(out).unwrap();
+}
+
 impl crate::formatters::traits::DiffFormatter for DiffMarkdownFormatter {
+    fn print_files(&self, files: &[FileDiff], hunks: &HunkOptions, out: &mut dyn Write) {
+        let changed: Vec<&FileDiff> = files.iter().filter(|d| d.status != FileStatus::Unchanged).collect();
+        if changed.is_empty() {
+            writeln!(out, "No changes.\n").unwrap();
+            return;
+        }
+
+        writeln!(out, "## 📄 Files\n").unwrap();
+        writeln!(out, "| Status | Path | Similarity |").unwrap();
+        writeln!(out, "| ------ | ---- | ---------: |").unwrap();
+        for d in &changed {
+            let path = match (&d.from, &d.to) {
+                (Some(f), Some(t)) => pair_label(f, t),
+                (Some(f), None) => f.path.clone(),
+                (None, Some(t)) => t.path.clone(),
+                (None, None) => continue,
+            };
+            let similarity = d.similarity.map(|s| format!("{}%", s)).unwrap_or_default();
+            writeln!(out, "| {} | `{}` | {} |", d.status.as_str(), path, similarity).unwrap();
+        }
+        writeln!(out).unwrap();
+
+        let mut heading = false;
+        for d in changed {
+            let from = d.from.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+            let to = d.to.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+            let hunks = compute_hunks(from, to, hunks);
+            if hunks.is_empty() {
+                continue;
+            }
+
+            if !heading {
+                writeln!(out, "## ✏️ Changes\n").unwrap();
+                heading = true;
+            }
+
+            let title = match (&d.from, &d.to) {
+                (Some(f), Some(t)) => pair_label(f, t),
+                (Some(f), None) => format!("{} (removed)", f.path),
+                (None, Some(t)) => format!("{} (added)", t.path),
+                (None, None) => continue,
+            };
+
+            let body: String = hunks
+                .iter()
+                .flat_map(|h| {
+                    let header = format!(
+                        "@@ -{},{} +{},{} @@\n",
+                        h.old_start, h.old_lines, h.new_start, h.new_lines
+                    );
+                    std::iter::once(header).chain(h.lines.iter().map(|l| format!("{}{}\n", l.op, l.text)))
+                })
+                .collect();
+            let fence = fence_for(&body);
+
+            writeln!(out, "### {}\n", title).unwrap();
+            write!(out, "{}diff\n{}{}\n\n", fence, body, fence).unwrap();
+        }
+    }
+
     fn print_preamble(&self, out: &mut dyn Write) {
-        writeln!(out, "# 📦 Cross-file Diff Report\n").unwrap();
+        writeln!(out, "## 🔀 Cross-file Moves\n").unwrap();
     }
 
-    fn print_index(&self, groups: &Vec<GroupedMatches>, out: &mut dyn Write) {
-        writeln!(out, "## 📄 File Pairs\n").unwrap();
+    fn print_index(&self, groups: &[GroupedMatches], out: &mut dyn Write) {
         for g in groups {
-            writeln!(out, "- {} → {}", g.from_file, g.to_file).unwrap();
+            writeln!(out, "- `{}` → `{}`", g.from_file, g.to_file).unwrap();
         }
         writeln!(out).unwrap();
     }
 
-    fn print_contents(&self, groups: &Vec<GroupedMatches>, out: &mut dyn Write) {
-        writeln!(out, "## 📑 Diff Contents\n").unwrap();
-
+    /// Per pair: every moved block with its tag and destination, then its
+    /// source lines, numbered, in a fence tagged with the file's language.
+    fn print_contents(&self, groups: &[GroupedMatches], out: &mut dyn Write) {
         for g in groups {
             writeln!(out, "### {} → {}\n", g.from_file, g.to_file).unwrap();
-            writeln!(out, "```python").unwrap();
-
-            if let Ok(src) = fs::read_to_string(&g.from_file) {
-                let lines: Vec<&str> = src.lines().collect();
-                let max_len = lines.iter().map(|l| l.len()).max().unwrap_or(0);
-
-                for (lineno, line) in lines.iter().enumerate() {
-                    let mut tag = String::new();
-
-                    for bwv in &g.blocks {
-                        let m = &bwv.block;
-                        let status = if bwv.is_addition { "[ADDED]" } else { "[MOVED]" };
-
-                        if lineno >= m.from_range.0 && lineno < m.from_range.1 {
-                            tag = format!(
-                                " // {} ({}–{} → {}–{})",
-                                status,
-                                m.from_range.0,
-                                m.from_range.1,
-                                m.to_file,
-                                m.to_range.0
-                            );
-                            break;
-                        }
-                    }
-
-                    writeln!(out, "{:>4} {}", lineno + 1, line).unwrap();
-                    if !tag.is_empty() {
-                        writeln!(out, "{:>width$}{}", "", tag, width = 6 + max_len).unwrap();
-                    }
-                }
+            let lines: Vec<&str> = g.from_content.lines().collect();
+
+            for bwv in &g.blocks {
+                let m = &bwv.block;
+                writeln!(
+                    out,
+                    "- {} lines {}–{} → `{}` lines {}–{}\n",
+                    block_tag(bwv),
+                    m.from_range.0 + 1,
+                    m.from_range.1,
+                    m.to_file,
+                    m.to_range.0 + 1,
+                    m.to_range.1,
+                ).unwrap();
+
+                let body: String = lines
+                    .iter()
+                    .enumerate()
+                    .take(m.from_range.1)
+                    .skip(m.from_range.0)
+                    .map(|(i, line)| format!("{:>4} {}\n", i + 1, line))
+                    .collect();
+                let fence = fence_for(&body);
+                write!(out, "{}{}\n{}{}\n\n", fence, code_lang(&g.from_file), body, fence).unwrap();
             }
+        }
+    }
 
-            writeln!(out, "```\n").unwrap();
+    fn render(&self, report: &DiffReport, out: &mut dyn Write) {
+        writeln!(out, "# 📦 Diff Report\n").unwrap();
+        write_summary(&report.files, out);
+        self.print_files(&report.files, &report.hunks, out);
+
+        if !report.moves.is_empty() {
+            self.print_preamble(out);
+            self.print_index(&report.moves, out);
+            self.print_contents(&report.moves, out);
         }
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::traits::DiffFormatter;
+    use crate::types::{BlockMatch, BlockWithVote, SourceFile};
+
+    fn source(path: &str, content: &str) -> SourceFile {
+        SourceFile { rel: path.into(), path: path.into(), content: content.into() }
+    }
+
+    #[test]
+    fn test_markdown_report_sections() {
+        let report = DiffReport {
+            files: vec![
+                FileDiff {
+                    status: FileStatus::Modified,
+                    from: Some(source("a.rs", "fn a() {}\nlet x = 1;\n")),
+                    to: Some(source("a.rs", "fn a() {}\nlet x = 2;\n")),
+                    similarity: None,
+                },
+                FileDiff { status: FileStatus::Added, from: None, to: Some(source("b.rs", "```\n")), similarity: None },
+            ],
+            moves: vec![GroupedMatches {
+                from_file: "a.rs".into(),
+                to_file: "c.rs".into(),
+                blocks: vec![BlockWithVote {
+                    block: BlockMatch {
+                        from_file: "a.rs".into(),
+                        from_range: (0, 1),
+                        to_file: "c.rs".into(),
+                        to_range: (4, 5),
+                        similarity: 100,
+                    },
+                    is_addition: false,
+                }],
+                from_content: "fn a() {}\n".into(),
+            }],
+            hunks: HunkOptions::default(),
+        };
+
+        let mut buf = Vec::new();
+        DiffMarkdownFormatter.render(&report, &mut buf);
+        let out = String::from_utf8(buf).unwrap();
+
+        assert!(out.contains("| modified | 1 |\n"));
+        assert!(out.contains("| added | `b.rs` |  |"));
+        assert!(out.contains("```diff\n@@ -1,2 +1,2 @@\n fn a() {}\n-let x = 1;\n+let x = 2;\n```"));
+        // content with its own fence gets a longer one
+        assert!(out.contains("````diff\n@@ -0,0 +1,1 @@\n+```\n````"));
+        assert!(out.contains("- [MOVED] lines 1–1 → `c.rs` lines 5–5\n\n```rust\n   1 fn a() {}\n```"));
+        assert!(!out.contains("python"));
+    }
+}
@@ -1,5 +1,89 @@
 pub mod cli;
+pub mod html;
+pub mod json;
 pub mod markdown;
+pub mod side_by_side;
+pub mod stat;
+pub mod semantic;
+pub mod verify;
 
 pub use cli::DiffCliFormatter;
+pub use html::DiffHtmlFormatter;
+pub use json::DiffJsonFormatter;
 pub use markdown::DiffMarkdownFormatter;
+pub use side_by_side::DiffSideBySideFormatter;
+pub use stat::DiffStatFormatter;
+pub use semantic::DiffSemanticFormatter;
+pub use verify::DiffVerifyFormatter;
+
+use std::io::Write;
+
+use crate::types::{BlockWithVote, FileDiff, FileStatus, SourceFile};
+
+/// Header for a paired file: one path when both sides share it, else `from → to`.
+fn pair_label(from: &SourceFile, to: &SourceFile) -> String {
+    if from.path == to.path {
+        from.path.clone()
+    } else {
+        format!("{} → {}", from.path, to.path)
+    }
+}
+
+/// Escape text for HTML element content and attribute values.
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Line segments as HTML, changed pieces wrapped in `tag` (`del` / `ins`).
+fn emphasised_html(segments: &[(bool, String)], tag: &str) -> String {
+    segments
+        .iter()
+        .map(|(emphasised, s)| match emphasised {
+            true => format!("<{tag}>{}</{tag}>", escape_html(s)),
+            false => escape_html(s),
+        })
+        .collect()
+}
+
+/// `[MOVED]` / `[ADDED]`, with the similarity when the block was edited.
+fn block_tag(bwv: &BlockWithVote) -> String {
+    let kind = if bwv.is_addition { "ADDED" } else { "MOVED" };
+    match bwv.block.similarity {
+        100 => format!("[{}]", kind),
+        sim => format!("[{} ~{}%]", kind, sim),
+    }
+}
+
+/// `- removed`, `+ added`, `R087 from → to` / `C075 from → to`, in pairing
+/// order; `body` renders the content diff of every pair whose contents differ.
+fn write_file_changes(
+    files: &[FileDiff],
+    out: &mut dyn Write,
+    body: impl Fn(&SourceFile, &SourceFile, &mut dyn Write),
+) {
+    for d in files {
+        match (d.status, &d.from, &d.to) {
+            (FileStatus::Removed, Some(f), _) => writeln!(out, "- {}", f.path).unwrap(),
+            (FileStatus::Added, _, Some(t)) => writeln!(out, "+ {}", t.path).unwrap(),
+            (FileStatus::Renamed | FileStatus::Copied, Some(f), Some(t)) => {
+                let letter = if d.status == FileStatus::Copied { 'C' } else { 'R' };
+                writeln!(out, "{}{:03} {} → {}", letter, d.similarity.unwrap_or(0), f.path, t.path).unwrap();
+                if f.content != t.content {
+                    body(f, t, out);
+                }
+            }
+            (FileStatus::Modified, Some(f), Some(t)) => body(f, t, out),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::io::Write;
+
+use colored::*;
+
+use crate::diff::semantic::{semantic_diff, ChangeKind, ItemChange};
+use crate::formatters::traits::DiffFormatter;
+use crate::types::{DiffReport, GroupedMatches};
+
+/// `--semantic`: changed functions, classes and types of Python and Rust
+/// files, grouped by file, instead of changed lines.
+pub struct DiffSemanticFormatter {
+    pub colored: bool,
+}
+
+/// Heading an item change is listed under: its file, or `from → to` when
+/// it moved between files.
+fn heading(change: &ItemChange) -> String {
+    match (&change.old, &change.new) {
+        (Some(o), Some(n)) if change.moved => format!("{} → {}", o.path, n.path),
+        _ => change.current().path.clone(),
+    }
+}
+
+fn symbol(kind: ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::Added => "+",
+        ChangeKind::Removed => "-",
+        ChangeKind::Renamed => "↻",
+        ChangeKind::Moved => "⇢",
+        ChangeKind::Modified => "~",
+    }
+}
+
+/// `renamed, body changed`, …: everything that happened to the item.
+fn details(change: &ItemChange) -> String {
+    let kind = change.change();
+    let mut parts = Vec::new();
+    if kind != ChangeKind::Modified {
+        parts.push(kind.as_str());
+    }
+    if kind == ChangeKind::Renamed && change.moved {
+        parts.push("moved");
+    }
+    if change.signature_changed() {
+        parts.push("signature changed");
+    }
+    if change.body_changed() {
+        parts.push("body changed");
+    }
+    parts.join(", ")
+}
+
+impl DiffSemanticFormatter {
+    fn paint(&self, text: String, kind: ChangeKind) -> String {
+        if !self.colored {
+            return text;
+        }
+        match kind {
+            ChangeKind::Added => text.bright_green().to_string(),
+            ChangeKind::Removed => text.bright_red().to_string(),
+            ChangeKind::Renamed => text.cyan().to_string(),
+            ChangeKind::Moved => text.magenta().to_string(),
+            ChangeKind::Modified => text.yellow().to_string(),
+        }
+    }
+
+    fn write_signature(&self, sign: char, signature: &str, out: &mut dyn Write) {
+        let line = format!("      {} {}", sign, signature);
+        let line = match (self.colored, sign) {
+            (false, _) => line,
+            (true, '-') => line.red().to_string(),
+            (true, _) => line.green().to_string(),
+        };
+        writeln!(out, "{}", line).unwrap();
+    }
+}
+
+impl DiffFormatter for DiffSemanticFormatter {
+    fn print_preamble(&self, _out: &mut dyn Write) {}
+
+    fn print_index(&self, _groups: &[GroupedMatches], _out: &mut dyn Write) {}
+
+    fn print_contents(&self, _groups: &[GroupedMatches], _out: &mut dyn Write) {}
+
+    fn render(&self, report: &DiffReport, out: &mut dyn Write) {
+        let diff = semantic_diff(report);
+
+        if self.colored {
+            writeln!(out, "{}", "🧬 Semantic diff".bright_magenta().bold()).unwrap();
+        } else {
+            writeln!(out, "🧬 Semantic diff").unwrap();
+        }
+
+        let mut groups: Vec<(String, Vec<&ItemChange>)> = Vec::new();
+        for change in &diff.changes {
+            let heading = heading(change);
+            match groups.iter_mut().find(|(h, _)| *h == heading) {
+                Some((_, changes)) => changes.push(change),
+                None => groups.push((heading, vec![change])),
+            }
+        }
+
+        for (heading, changes) in &groups {
+            writeln!(out).unwrap();
+            if self.colored {
+                writeln!(out, "📄 {}", heading.bold()).unwrap();
+            } else {
+                writeln!(out, "📄 {}", heading).unwrap();
+            }
+
+            let name = |c: &ItemChange| match (&c.old, &c.new) {
+                (Some(o), Some(n)) if o.item.name != n.item.name => format!("{} → {}", o.item.name, n.item.name),
+                _ => c.current().item.name.clone(),
+            };
+            let width = changes.iter().map(|c| name(c).chars().count()).max().unwrap_or(0);
+
+            for change in changes {
+                let kind = change.change();
+                let row = format!(
+                    "  {} {:<8} {:<width$}  {}",
+                    symbol(kind),
+                    change.kind().as_str(),
+                    name(change),
+                    details(change),
+                    width = width
+                );
+                writeln!(out, "{}", self.paint(row, kind)).unwrap();
+
+                if let (true, Some(o), Some(n)) = (change.signature_changed(), &change.old, &change.new) {
+                    self.write_signature('-', &o.item.signature, out);
+                    self.write_signature('+', &n.item.signature, out);
+                }
+            }
+        }
+
+        let count = |kind: ChangeKind| diff.changes.iter().filter(|c| c.change() == kind).count();
+        let kinds = [ChangeKind::Added, ChangeKind::Removed, ChangeKind::Modified, ChangeKind::Renamed, ChangeKind::Moved];
+        writeln!(out).unwrap();
+        if diff.changes.is_empty() {
+            writeln!(out, "No item-level changes").unwrap();
+        } else {
+            let summary: Vec<String> = kinds.iter().map(|&k| format!("{} {}", count(k), k.as_str())).collect();
+            let n = diff.changes.len();
+            writeln!(out, "{} item{} changed: {}", n, if n == 1 { "" } else { "s" }, summary.join(", ")).unwrap();
+        }
+
+        if !diff.skipped.is_empty() {
+            writeln!(out, "Not parsed (not Python or Rust): {}", diff.skipped.join(", ")).unwrap();
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileDiff, FileStatus, SourceFile};
+
+    fn source(path: &str, content: &str) -> Option<SourceFile> {
+        Some(SourceFile { rel: path.into(), path: path.into(), content: content.into() })
+    }
+
+    #[test]
+    fn test_semantic_report() {
+        let report = DiffReport {
+            files: vec![
+                FileDiff {
+                    status: FileStatus::Modified,
+                    from: source("lib.rs", "fn area(w: u32, h: u32) -> u32 {\n    w * h\n}\n\nfn old() {}\n"),
+                    to: source("lib.rs", "fn area(w: u64, h: u64) -> u64 {\n    w * h\n}\n"),
+                    similarity: None,
+                },
+                FileDiff { status: FileStatus::Added, from: None, to: source("README.md", "hi\n"), similarity: None },
+            ],
+            ..DiffReport::default()
+        };
+        let mut buf = Vec::new();
+        DiffSemanticFormatter { colored: false }.render(&report, &mut buf);
+        let out = String::from_utf8(buf).unwrap();
+
+        assert!(out.contains("📄 lib.rs\n"));
+        assert!(out.contains("  ~ function area  signature changed\n"));
+        assert!(out.contains("      - fn area(w: u32, h: u32) -> u32\n      + fn area(w: u64, h: u64) -> u64\n"));
+        assert!(out.contains("  - function old   removed\n"));
+        assert!(out.contains("2 items changed: 0 added, 1 removed, 1 modified, 0 renamed, 0 moved\n"));
+        assert!(out.contains("Not parsed (not Python or Rust): README.md\n"));
+    }
+}
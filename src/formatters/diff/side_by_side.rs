@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use colored::*;
+use similar::ChangeTag;
+use unicode_width::UnicodeWidthChar;
+
+use crate::diff::hunks::{line_hunks, LineHunk};
+use crate::diff::inline::{paint, Emphasis, InlineLine};
+use crate::diff::options::HunkOptions;
+use crate::formatters::traits::DiffFormatter;
+use crate::types::{DiffReport, FileDiff, GroupedMatches, SourceFile};
+use super::{block_tag, pair_label, write_file_changes};
+
+/// Width used when stdout is not a terminal and `COLUMNS` is unset.
+const FALLBACK_WIDTH: usize = 120;
+
+/// Narrowest column we are willing to render.
+const MIN_COLUMN: usize = 20;
+
+/// Line numbers, spaces and the centre marker around the two columns.
+const CHROME: usize = 13;
+
+/// Width of the terminal on stdout, else `$COLUMNS`, else 120.
+pub fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(w, _)| w.0 as usize)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(FALLBACK_WIDTH)
+}
+
+/// Two-column hunks with changed words emphasised. Lines that belong to a
+/// cross-file move are marked `⇢` (moved away) / `⇠` (moved in) in the centre
+/// gutter, with the destination noted above the block.
+pub struct DiffSideBySideFormatter {
+    pub colored: bool,
+    pub emphasis: Emphasis,
+    /// Total output width in columns
+    pub width: usize,
+}
+
+/// Cross-file moves by (path, 0-based line): the first line of a block
+/// carries its note, every line of it carries the marker.
+#[derive(Default)]
+struct MoveNotes {
+    away: HashMap<(String, usize), Option<String>>,
+    into: HashMap<(String, usize), Option<String>>,
+}
+
+impl MoveNotes {
+    fn new(groups: &[GroupedMatches]) -> Self {
+        let mut notes = Self::default();
+
+        for g in groups {
+            for bwv in &g.blocks {
+                let m = &bwv.block;
+                let tag = block_tag(bwv);
+                let (f1, f2) = m.from_range;
+                let (t1, t2) = m.to_range;
+
+                for line in f1..f2 {
+                    let note = (line == f1).then(|| format!("⇢ {} to {}:{}", tag, m.to_file, t1 + 1));
+                    notes.away.insert((g.from_file.clone(), line), note);
+                }
+                for line in t1..t2 {
+                    let note = (line == t1).then(|| format!("⇠ {} from {}:{}", tag, g.from_file, f1 + 1));
+                    notes.into.insert((m.to_file.clone(), line), note);
+                }
+            }
+        }
+
+        notes
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowKind {
+    Equal,
+    Changed,
+    Removed,
+    Added,
+}
+
+/// One output row: 1-based line number and segments per side.
+struct Row<'a> {
+    kind: RowKind,
+    old: Option<(usize, &'a [(bool, String)])>,
+    new: Option<(usize, &'a [(bool, String)])>,
+}
+
+/// 1-based line number and segments of one side of a line.
+fn side(index: Option<usize>, line: &InlineLine) -> Option<(usize, &[(bool, String)])> {
+    index.map(|n| (n + 1, &line.segments[..]))
+}
+
+/// Lay a hunk out in rows; a run of removals followed by additions is
+/// paired line by line, the longer side's leftovers stand alone.
+fn hunk_rows(hunk: &LineHunk) -> Vec<Row<'_>> {
+    let mut rows = Vec::new();
+    let mut i = 0;
+
+    while i < hunk.lines.len() {
+        let line = &hunk.lines[i];
+        if line.tag == ChangeTag::Equal {
+            rows.push(Row { kind: RowKind::Equal, old: side(line.old_index, line), new: side(line.new_index, line) });
+            i += 1;
+            continue;
+        }
+
+        let dels: Vec<&InlineLine> = hunk.lines[i..].iter().take_while(|l| l.tag == ChangeTag::Delete).collect();
+        i += dels.len();
+        let adds: Vec<&InlineLine> = hunk.lines[i..].iter().take_while(|l| l.tag == ChangeTag::Insert).collect();
+        i += adds.len();
+
+        for k in 0..dels.len().max(adds.len()) {
+            let old = dels.get(k).and_then(|l| side(l.old_index, l));
+            let new = adds.get(k).and_then(|l| side(l.new_index, l));
+            let kind = match (old, new) {
+                (Some(_), Some(_)) => RowKind::Changed,
+                (Some(_), None) => RowKind::Removed,
+                _ => RowKind::Added,
+            };
+            rows.push(Row { kind, old, new });
+        }
+    }
+
+    rows
+}
+
+impl DiffSideBySideFormatter {
+    fn column(&self) -> usize {
+        (self.width.saturating_sub(CHROME) / 2).max(MIN_COLUMN)
+    }
+
+    /// Clip `segments` to the column (`…` marks a cut) and pad it, colouring
+    /// removed/added text and emphasising changed words.
+    fn cell(&self, segments: &[(bool, String)], kind: RowKind, left: bool) -> String {
+        let width = self.column();
+        let mut out = String::new();
+        let mut used = 0;
+
+        let total: usize = segments
+            .iter()
+            .flat_map(|(_, s)| s.chars())
+            .map(|c| if c == '\t' { 4 } else { c.width().unwrap_or(0) })
+            .sum();
+        let budget = if total > width { width - 1 } else { width };
+
+        'segments: for (emphasised, text) in segments {
+            let mut piece = String::new();
+            for c in text.chars() {
+                let (c, w) = match c {
+                    '\t' => ("    ".to_string(), 4),
+                    c => (c.to_string(), c.width().unwrap_or(0)),
+                };
+                if used + w > budget {
+                    out.push_str(&self.paint(&piece, kind, left, *emphasised));
+                    break 'segments;
+                }
+                piece.push_str(&c);
+                used += w;
+            }
+            out.push_str(&self.paint(&piece, kind, left, *emphasised));
+        }
+
+        if total > width {
+            out.push('…');
+            used += 1;
+        }
+        out.push_str(&" ".repeat(width.saturating_sub(used)));
+        out
+    }
+
+    fn paint(&self, text: &str, kind: RowKind, left: bool, emphasised: bool) -> String {
+        if kind == RowKind::Equal {
+            return text.to_string();
+        }
+        paint(&[(emphasised, text.to_string())], self.colored, left)
+    }
+
+    fn note_row(&self, left: Option<&str>, right: Option<&str>, out: &mut dyn Write) {
+        let width = self.column();
+        let fit = |s: Option<&str>| {
+            let s: String = s.unwrap_or("").chars().take(width).collect();
+            let pad = width.saturating_sub(s.chars().count());
+            let s = if self.colored { s.bright_yellow().to_string() } else { s };
+            format!("{}{}", s, " ".repeat(pad))
+        };
+        let line = format!("     {}   {:>4} {}", fit(left), "", fit(right));
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+
+    fn print_pair(&self, from: &SourceFile, to: &SourceFile, hunks: &HunkOptions, notes: &MoveNotes, out: &mut dyn Write) {
+        let hunks = line_hunks(&from.content, &to.content, hunks, self.emphasis);
+        if hunks.is_empty() {
+            return;
+        }
+
+        let header = format!("📄 {}", pair_label(from, to));
+        if self.colored {
+            writeln!(out, "\n{}\n", header.bold()).unwrap();
+        } else {
+            writeln!(out, "\n{}\n", header).unwrap();
+        }
+
+        for hunk in hunks {
+            let range = hunk.header();
+            if self.colored {
+                writeln!(out, "{}", range.cyan()).unwrap();
+            } else {
+                writeln!(out, "{}", range).unwrap();
+            }
+
+            for row in hunk_rows(&hunk) {
+                let away = row.old.and_then(|(n, _)| notes.away.get(&(from.path.clone(), n - 1)));
+                let into = row.new.and_then(|(n, _)| notes.into.get(&(to.path.clone(), n - 1)));
+
+                let moved_away = away.is_some() && row.kind != RowKind::Equal;
+                let moved_in = into.is_some() && row.kind != RowKind::Equal;
+                let note_away = away.and_then(|n| n.as_deref()).filter(|_| moved_away);
+                let note_into = into.and_then(|n| n.as_deref()).filter(|_| moved_in);
+                if note_away.is_some() || note_into.is_some() {
+                    self.note_row(note_away, note_into, out);
+                }
+
+                let left = row.old.map(|(_, s)| s).unwrap_or_default();
+                let right = row.new.map(|(_, s)| s).unwrap_or_default();
+
+                let marker = match row.kind {
+                    _ if moved_away => '⇢',
+                    _ if moved_in => '⇠',
+                    RowKind::Equal => '│',
+                    RowKind::Changed => '|',
+                    RowKind::Removed => '<',
+                    RowKind::Added => '>',
+                };
+                let number = |side: Option<(usize, &[(bool, String)])>| {
+                    side.map(|(n, _)| n.to_string()).unwrap_or_default()
+                };
+
+                let line = format!(
+                    "{:>4} {} {} {:>4} {}",
+                    number(row.old),
+                    self.cell(left, row.kind, true),
+                    marker,
+                    number(row.new),
+                    self.cell(right, row.kind, false),
+                );
+                writeln!(out, "{}", line.trim_end()).unwrap();
+            }
+        }
+    }
+
+    fn print_pairs(&self, files: &[FileDiff], hunks: &HunkOptions, notes: &MoveNotes, out: &mut dyn Write) {
+        write_file_changes(files, out, |f, t, out| self.print_pair(f, t, hunks, notes, out));
+    }
+}
+
+impl DiffFormatter for DiffSideBySideFormatter {
+    fn print_files(&self, files: &[FileDiff], hunks: &HunkOptions, out: &mut dyn Write) {
+        self.print_pairs(files, hunks, &MoveNotes::default(), out);
+    }
+
+    fn print_preamble(&self, out: &mut dyn Write) {
+        if self.colored {
+            writeln!(out, "\n{}\n", "📦 Cross-file Moves".bright_magenta().bold()).unwrap();
+        } else {
+            writeln!(out, "\n📦 Cross-file Moves\n").unwrap();
+        }
+    }
+
+    fn print_index(&self, _groups: &[GroupedMatches], _out: &mut dyn Write) {}
+
+    /// One line per block; the rows themselves were shown in the gutter.
+    fn print_contents(&self, groups: &[GroupedMatches], out: &mut dyn Write) {
+        for g in groups {
+            for bwv in &g.blocks {
+                let m = &bwv.block;
+                writeln!(
+                    out,
+                    "{} {}:{}–{} → {}:{}–{}",
+                    block_tag(bwv),
+                    g.from_file, m.from_range.0 + 1, m.from_range.1,
+                    m.to_file, m.to_range.0 + 1, m.to_range.1,
+                ).unwrap();
+            }
+        }
+    }
+
+    fn render(&self, report: &DiffReport, out: &mut dyn Write) {
+        self.print_pairs(&report.files, &report.hunks, &MoveNotes::new(&report.moves), out);
+
+        if !report.moves.is_empty() {
+            self.print_preamble(out);
+            self.print_contents(&report.moves, out);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileStatus;
+
+    fn source(path: &str, content: &str) -> SourceFile {
+        SourceFile { rel: path.into(), path: path.into(), content: content.into() }
+    }
+
+    fn render(from: &str, to: &str, width: usize) -> String {
+        let report = DiffReport {
+            files: vec![FileDiff {
+                status: FileStatus::Modified,
+                from: Some(source("a.py", from)),
+                to: Some(source("a.py", to)),
+                similarity: None,
+            }],
+            moves: Vec::new(),
+            hunks: HunkOptions::default(),
+        };
+        let mut buf = Vec::new();
+        DiffSideBySideFormatter { colored: false, emphasis: Emphasis::Word, width }.render(&report, &mut buf);
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_changed_lines_share_a_row() {
+        let out = render("a = 1\nb = 2\nc = 3\n", "a = 1\nb = 20\nc = 3\nd = 4\n", 80);
+        let rows: Vec<&str> = out.lines().filter(|l| l.contains('|') || l.contains('>')).collect();
+
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("   2 b = 2"));
+        assert!(rows[0].ends_with("   2 b = 20"));
+        assert!(rows[1].ends_with("   4 d = 4"));
+        assert!(out.contains("@@ -1,3 +1,4 @@"));
+    }
+
+    #[test]
+    fn test_long_lines_are_clipped_to_the_column() {
+        let long = "x".repeat(200);
+        let out = render(&format!("{}\n", long), "y\n", 60);
+        let row = out.lines().find(|l| l.contains('|')).unwrap();
+
+        assert!(row.contains('…'));
+        assert!(row.chars().count() <= 60);
+    }
+}
@@ -0,0 +1,189 @@
+use std::io::Write;
+
+use colored::*;
+
+use crate::diff::hunks::compute_hunks;
+use crate::diff::options::HunkOptions;
+use crate::formatters::traits::DiffFormatter;
+use crate::types::{DiffReport, FileDiff, FileStatus, GroupedMatches};
+
+/// Longest histogram bar; larger changes are scaled down to fit.
+const MAX_BAR: usize = 40;
+
+/// `git diff --stat`-style overview: per-file insertions, deletions and
+/// lines taking part in cross-file moves, then totals.
+pub struct DiffStatFormatter {
+    pub colored: bool,
+}
+
+/// Counts for one changed file.
+struct FileStat {
+    label: String,
+    insertions: usize,
+    deletions: usize,
+    moved: usize,
+}
+
+fn file_stats(files: &[FileDiff], moves: &[GroupedMatches], hunks: &HunkOptions) -> Vec<FileStat> {
+    files
+        .iter()
+        .filter(|d| d.status != FileStatus::Unchanged)
+        .map(|d| {
+            let from = d.from.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+            let to = d.to.as_ref().map(|f| f.content.as_str()).unwrap_or("");
+            let lines: Vec<char> = compute_hunks(from, to, hunks)
+                .into_iter()
+                .flat_map(|h| h.lines.into_iter().map(|l| l.op))
+                .collect();
+
+            // lines moved out of the old side plus lines moved into the new one
+            let from_path = d.from.as_ref().map(|f| f.path.as_str());
+            let to_path = d.to.as_ref().map(|f| f.path.as_str());
+            let moved = moves
+                .iter()
+                .flat_map(|g| g.blocks.iter().map(move |b| (g, &b.block)))
+                .map(|(g, m)| {
+                    let out = if Some(g.from_file.as_str()) == from_path { m.from_range.1 - m.from_range.0 } else { 0 };
+                    let into = if Some(m.to_file.as_str()) == to_path { m.to_range.1 - m.to_range.0 } else { 0 };
+                    out + into
+                })
+                .sum();
+
+            let label = match (d.status, &d.from, &d.to) {
+                (FileStatus::Renamed | FileStatus::Copied, Some(f), Some(t)) => format!("{} → {}", f.rel, t.rel),
+                _ => d.rel().to_string(),
+            };
+
+            FileStat {
+                label,
+                insertions: lines.iter().filter(|&&op| op == '+').count(),
+                deletions: lines.iter().filter(|&&op| op == '-').count(),
+                moved,
+            }
+        })
+        .collect()
+}
+
+fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+impl DiffStatFormatter {
+    /// `+++--` scaled so the largest change fits in `MAX_BAR` columns; any
+    /// non-zero count keeps at least one mark.
+    fn bar(&self, stat: &FileStat, largest: usize) -> String {
+        let scale = |n: usize| match largest {
+            l if l <= MAX_BAR => n,
+            l => (n * MAX_BAR / l).max((n > 0) as usize),
+        };
+        let plus = "+".repeat(scale(stat.insertions));
+        let minus = "-".repeat(scale(stat.deletions));
+
+        if self.colored {
+            format!("{}{}", plus.green(), minus.red())
+        } else {
+            format!("{}{}", plus, minus)
+        }
+    }
+}
+
+impl DiffFormatter for DiffStatFormatter {
+    fn print_preamble(&self, _out: &mut dyn Write) {}
+
+    fn print_index(&self, _groups: &[GroupedMatches], _out: &mut dyn Write) {}
+
+    /// `N blocks, M lines moved between K file pairs`
+    fn print_contents(&self, groups: &[GroupedMatches], out: &mut dyn Write) {
+        let blocks: usize = groups.iter().map(|g| g.blocks.len()).sum();
+        let lines: usize = groups
+            .iter()
+            .flat_map(|g| &g.blocks)
+            .map(|b| b.block.from_range.1 - b.block.from_range.0)
+            .sum();
+
+        writeln!(
+            out,
+            " moves: {}, {} moved between {}",
+            plural(blocks, "block", "blocks"),
+            plural(lines, "line", "lines"),
+            plural(groups.len(), "file pair", "file pairs"),
+        ).unwrap();
+    }
+
+    fn render(&self, report: &DiffReport, out: &mut dyn Write) {
+        let stats = file_stats(&report.files, &report.moves, &report.hunks);
+
+        let label_width = stats.iter().map(|s| s.label.chars().count()).max().unwrap_or(0);
+        let count_width = stats
+            .iter()
+            .map(|s| (s.insertions + s.deletions).to_string().len())
+            .max()
+            .unwrap_or(1);
+        let largest = stats.iter().map(|s| s.insertions + s.deletions).max().unwrap_or(0);
+
+        for s in &stats {
+            let moved = match s.moved {
+                0 => String::new(),
+                n => format!(" ({} moved)", n),
+            };
+            let line = format!(
+                " {:<lw$} | {:>cw$} {}{}",
+                s.label,
+                s.insertions + s.deletions,
+                self.bar(s, largest),
+                moved,
+                lw = label_width,
+                cw = count_width,
+            );
+            writeln!(out, "{}", line.trim_end()).unwrap();
+        }
+
+        let insertions: usize = stats.iter().map(|s| s.insertions).sum();
+        let deletions: usize = stats.iter().map(|s| s.deletions).sum();
+        writeln!(
+            out,
+            " {} changed, {}(+), {}(-)",
+            plural(stats.len(), "file", "files"),
+            plural(insertions, "insertion", "insertions"),
+            plural(deletions, "deletion", "deletions"),
+        ).unwrap();
+
+        if !report.moves.is_empty() {
+            self.print_contents(&report.moves, out);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SourceFile;
+
+    fn source(path: &str, content: &str) -> Option<SourceFile> {
+        Some(SourceFile { rel: path.into(), path: path.into(), content: content.into() })
+    }
+
+    #[test]
+    fn test_stat_counts_and_scales() {
+        let big: String = (0..100).map(|i| format!("{}\n", i)).collect();
+        let report = DiffReport {
+            files: vec![
+                FileDiff { status: FileStatus::Modified, from: source("a.py", "x\ny\n"), to: source("a.py", "x\nz\n"), similarity: None },
+                FileDiff { status: FileStatus::Added, from: None, to: source("big.py", &big), similarity: None },
+                FileDiff { status: FileStatus::Unchanged, from: source("same.py", "s\n"), to: source("same.py", "s\n"), similarity: None },
+            ],
+            ..DiffReport::default()
+        };
+
+        let mut buf = Vec::new();
+        DiffStatFormatter { colored: false }.render(&report, &mut buf);
+        let out = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[0], " a.py   |   2 +-");
+        assert_eq!(lines[1], format!(" big.py | 100 {}", "+".repeat(MAX_BAR)));
+        assert_eq!(lines[2], " 2 files changed, 101 insertions(+), 1 deletion(-)");
+        assert_eq!(lines.len(), 3);
+    }
+}
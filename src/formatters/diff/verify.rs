@@ -0,0 +1,150 @@
+use std::io::Write;
+
+use colored::*;
+use similar::ChangeTag;
+
+use crate::diff::policy::{LineChanges, LineClass};
+use crate::formatters::traits::DiffFormatter;
+use crate::types::{DiffReport, GroupedMatches};
+
+/// `--verify-move-only`: how many changed lines were moved, reformatted,
+/// added or removed, a verdict, and every line that was not a move.
+pub struct DiffVerifyFormatter {
+    pub colored: bool,
+}
+
+fn lines(n: usize) -> &'static str {
+    if n == 1 { "line" } else { "lines" }
+}
+
+impl DiffVerifyFormatter {
+    fn paint(&self, text: String, class: LineClass) -> String {
+        if !self.colored {
+            return text;
+        }
+        match class {
+            LineClass::Added => text.bright_green().to_string(),
+            LineClass::Removed => text.bright_red().to_string(),
+            _ => text,
+        }
+    }
+}
+
+impl DiffFormatter for DiffVerifyFormatter {
+    fn print_preamble(&self, _out: &mut dyn Write) {}
+
+    fn print_index(&self, _groups: &[GroupedMatches], _out: &mut dyn Write) {}
+
+    fn print_contents(&self, _groups: &[GroupedMatches], _out: &mut dyn Write) {}
+
+    fn render(&self, report: &DiffReport, out: &mut dyn Write) {
+        let changes = LineChanges::of(report);
+
+        if self.colored {
+            writeln!(out, "{}", "🔍 Move-only verification".bright_magenta().bold()).unwrap();
+        } else {
+            writeln!(out, "🔍 Move-only verification").unwrap();
+        }
+
+        // moved/reformatted lines exist on both sides; count them once
+        let rows = [
+            (LineClass::Moved, ChangeTag::Delete),
+            (LineClass::Reformatted, ChangeTag::Delete),
+            (LineClass::Added, ChangeTag::Insert),
+            (LineClass::Removed, ChangeTag::Delete),
+        ];
+        for (class, tag) in rows {
+            let n = changes.count(class, tag);
+            writeln!(out, "  {:<12} {:>5} {}", class.as_str(), n, lines(n)).unwrap();
+        }
+        writeln!(out).unwrap();
+
+        let residual: Vec<_> = changes.residual().collect();
+        if residual.is_empty() {
+            let verdict = "✅ Move-only: every changed line was moved or reformatted";
+            if self.colored {
+                writeln!(out, "{}", verdict.green().bold()).unwrap();
+            } else {
+                writeln!(out, "{}", verdict).unwrap();
+            }
+            return;
+        }
+
+        let verdict = format!("❌ Not move-only: {} {} changed outside moves", residual.len(), lines(residual.len()));
+        if self.colored {
+            writeln!(out, "{}", verdict.red().bold()).unwrap();
+        } else {
+            writeln!(out, "{}", verdict).unwrap();
+        }
+
+        writeln!(out, "\nResidual changes:").unwrap();
+        let width = residual
+            .iter()
+            .map(|l| l.path.chars().count() + l.line.to_string().len() + 1)
+            .max()
+            .unwrap_or(0);
+        for l in residual {
+            let sign = if l.class == LineClass::Added { '+' } else { '-' };
+            let location = format!("{}:{}", l.path, l.line);
+            let row = format!("  {} {:<width$}  {}", sign, location, l.text, width = width);
+            writeln!(out, "{}", self.paint(row, l.class)).unwrap();
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::engine::diff_sides;
+    use crate::diff::options::DiffOptions;
+    use crate::types::SourceFile;
+
+    fn source(path: &str, content: &str) -> SourceFile {
+        SourceFile { rel: path.into(), path: path.into(), content: content.into() }
+    }
+
+    fn render(from: &[SourceFile], to: &[SourceFile]) -> String {
+        let options = DiffOptions { verify_move_only: true, ..DiffOptions::default() };
+        let report = diff_sides(from, to, false, &options);
+        let mut buf = Vec::new();
+        DiffVerifyFormatter { colored: false }.render(&report, &mut buf);
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_split_module_is_move_only() {
+        let out = render(
+            &[source("a.rs", "fn a() {}\nfn b() {\n    1\n}\n")],
+            &[source("a.rs", "fn a() {}\n"), source("b.rs", "fn b() {\n  1\n}\n")],
+        );
+
+        assert!(out.contains("  moved            2 lines\n"));
+        assert!(out.contains("  reformatted      1 line\n"));
+        assert!(out.contains("✅ Move-only"));
+        assert!(!out.contains("Residual"));
+    }
+
+    #[test]
+    fn test_logic_change_is_residual() {
+        let out = render(
+            &[source("a.rs", "let x = 1;\nlet y = x;\n")],
+            &[source("a.rs", "let y = x;\nlet x = 2;\n")],
+        );
+
+        assert!(out.contains("❌ Not move-only: 2 lines changed outside moves"));
+        assert!(out.contains("  - a.rs:1  let x = 1;\n"));
+        assert!(out.contains("  + a.rs:2  let x = 2;\n"));
+    }
+
+    #[test]
+    fn test_reordered_statements_are_residual() {
+        let old = "def withdraw(acct, amt):\n    acct.balance -= amt\n    if acct.balance < 0:\n        raise Overdraft()\n";
+        let new = "def withdraw(acct, amt):\n    if acct.balance < 0:\n        raise Overdraft()\n    acct.balance -= amt\n";
+        let out = render(&[source("bank.py", old)], &[source("bank.py", new)]);
+
+        assert!(out.contains("❌ Not move-only"));
+        assert!(out.contains("  - bank.py:2      acct.balance -= amt\n"));
+        assert!(out.contains("  + bank.py:4      acct.balance -= amt\n"));
+    }
+}
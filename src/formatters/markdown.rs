@@ -1,11 +1,25 @@
-use std::fs;
 use std::io::Write;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::codex::integrity::short_hash;
+use crate::error::{Error, Result};
 use crate::types::FileEntry;
 
 use super::traits::OutputFormatter;
 
+/// Code-fence language for a path, by extension (`text` when it has none).
+pub fn code_lang(path: &str) -> &str {
+    match std::path::Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("rs") => "rust",
+        Some("py") => "python",
+        Some("tex") => "latex",
+        Some("md") => "markdown",
+        Some("js") | Some("ts") | Some("tsx") => "typescript",
+        Some(ext) => ext,
+        None => "text",
+    }
+}
+
 #[allow(dead_code)]
 pub struct MarkdownFormatter {
     pub show_lines: bool,
@@ -36,7 +50,7 @@ impl OutputFormatter for MarkdownFormatter {
         writeln!(out, "## INDEX").unwrap();
     }
 
-    fn print_index(&self, files: &Vec<FileEntry>, out: &mut dyn Write) {
+    fn print_index(&self, files: &[FileEntry], out: &mut dyn Write) {
 
         let mut total_lines = 0usize;
 
@@ -69,7 +83,7 @@ impl OutputFormatter for MarkdownFormatter {
 
         writeln!(
             out,
-            "{:<path_w$} : {:>line_w$} | {:>word_w$} | {:>token_w$}",
+            "{:<path_w$} : {:>line_w$} | {:>word_w$} | {:>token_w$} | hash",
             "path",
             "lines",
             "words",
@@ -88,11 +102,12 @@ impl OutputFormatter for MarkdownFormatter {
 
             writeln!(
                 out,
-                "{:<path_w$} : {:>line_w$} | {:>word_w$} | {:>token_w$}",
+                "{:<path_w$} : {:>line_w$} | {:>word_w$} | {:>token_w$} | {}",
                 entry.path,
                 entry.line_count,
                 entry.word_count,
                 entry.token_est,
+                short_hash(&entry.hash),
                 path_w = path_width,
                 line_w = line_width.max(5),
                 word_w = word_width.max(5),
@@ -104,38 +119,27 @@ impl OutputFormatter for MarkdownFormatter {
         writeln!(out, "## FILES").unwrap();
     }
 
-    fn print_contents(&self, files: &Vec<FileEntry>, out: &mut dyn Write) {
+    fn print_contents(&self, files: &[FileEntry], out: &mut dyn Write) -> Result<()> {
         for entry in files {
-            let lang = match entry.path.split('.').last() {
-                Some("rs") => "rust",
-                Some("py") => "python",
-                Some("tex") => "latex",
-                Some("md") => "markdown",
-                Some("js") | Some("ts") | Some("tsx") => "typescript",
-                Some(ext) => ext,
-                None => "text",
-            };
+            let lang = code_lang(&entry.path);
 
             writeln!(
                 out,
-                "<file path=\"{}\" lang=\"{}\" lines=\"{}\">",
-                entry.path, lang, entry.line_count
+                "<file path=\"{}\" lang=\"{}\" lines=\"{}\" hash=\"{}\">",
+                entry.path, lang, entry.line_count, entry.hash
             ).unwrap();
             writeln!(out, "```{}", lang).unwrap();
 
-            match fs::read_to_string(&entry.path) {
-                Ok(content) => {
-                    if content.ends_with('\n') {
-                        write!(out, "{}", content).unwrap();
-                    } else {
-                        writeln!(out, "{}", content).unwrap();
-                    }
-                }
-                Err(_) => writeln!(out, "❌ Error reading file").unwrap(),
+            let content = entry.read_contents().map_err(|e| Error::io(&entry.path, e))?;
+            if content.ends_with('\n') {
+                write!(out, "{}", content).unwrap();
+            } else {
+                writeln!(out, "{}", content).unwrap();
             }
 
             writeln!(out, "```\n</file>\n").unwrap();
         }
+        Ok(())
     }
 }
 
@@ -152,12 +156,18 @@ mod tests {
                 line_count: 10,
                 word_count: 20,
                 token_est: 27,
+                hash: "9f2c1a7be04d5e6f".into(),
+                blob: None,
+                text: None,
             },
             FileEntry {
                 path: "src/formatter.rs".into(),
                 line_count: 5,
                 word_count: 12,
                 token_est: 16,
+                hash: "03ab88c4d1e2f790".into(),
+                blob: None,
+                text: None,
             }
         ]
     }
@@ -185,6 +195,29 @@ mod tests {
         assert!(out.contains("src/main.rs"));
         assert!(out.contains("src/formatter.rs"));
         assert!(out.contains("total_loc: 15"));
+        assert!(out.contains("9f2c1a7be04d"));
+    }
+
+    #[test]
+    fn test_markdown_file_tag_carries_hash() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(tmp.path(), "fn main() {}\n").unwrap();
+
+        let files = vec![FileEntry {
+            path: tmp.path().to_string_lossy().to_string(),
+            line_count: 1,
+            word_count: 3,
+            token_est: 4,
+            hash: "deadbeef".into(),
+            blob: None,
+            text: None,
+        }];
+
+        let mut buf = Vec::new();
+        MarkdownFormatter { show_lines: false }.print_contents(&files, &mut buf).unwrap();
+        let out = String::from_utf8(buf).unwrap();
+
+        assert!(out.contains("hash=\"deadbeef\">"));
     }
 }
 
@@ -1,16 +1,34 @@
 use std::io::Write;
 
-use crate::types::{FileEntry, GroupedMatches};
+use crate::diff::options::HunkOptions;
+use crate::error::Result;
+use crate::types::{DiffReport, FileDiff, FileEntry, GroupedMatches};
 
 pub trait OutputFormatter {
     fn print_preamble(&self, root: &str, out: &mut dyn Write);
-    fn print_index(&self, files: &Vec<FileEntry>, out: &mut dyn Write);
-    fn print_contents(&self, files: &Vec<FileEntry>, out: &mut dyn Write);
+    fn print_index(&self, files: &[FileEntry], out: &mut dyn Write);
+    /// Fails on the first file that cannot be read.
+    fn print_contents(&self, files: &[FileEntry], out: &mut dyn Write) -> Result<()>;
 }
 
 pub trait DiffFormatter {
     fn print_preamble(&self, out: &mut dyn Write);
-    fn print_index(&self, groups: &Vec<GroupedMatches>, out: &mut dyn Write);
-    fn print_contents(&self, groups: &Vec<GroupedMatches>, out: &mut dyn Write);
+    fn print_index(&self, groups: &[GroupedMatches], out: &mut dyn Write);
+    fn print_contents(&self, groups: &[GroupedMatches], out: &mut dyn Write);
+
+    /// Per-file section: added/removed listing and inline diffs.
+    fn print_files(&self, _files: &[FileDiff], _hunks: &HunkOptions, _out: &mut dyn Write) {}
+
+    /// Render a whole report. The cross-file section is only emitted when
+    /// moves were found.
+    fn render(&self, report: &DiffReport, out: &mut dyn Write) {
+        self.print_files(&report.files, &report.hunks, out);
+
+        if !report.moves.is_empty() {
+            self.print_preamble(out);
+            self.print_index(&report.moves, out);
+            self.print_contents(&report.moves, out);
+        }
+    }
 }
 
@@ -0,0 +1,30 @@
+//! Yggdrasil — flatten any subset of a codebase into an AI-ready codex
+//! (index + contents), or compare snapshots with annotated diffs.
+//!
+//! The `ygg` binary is a thin wrapper over this crate. To embed snapshotting
+//! in your own tools, start from [`Snapshot::builder`]; everything else is
+//! internal.
+
+pub(crate) mod types;
+pub(crate) mod error;
+pub(crate) mod codex;
+pub(crate) mod scanner;
+pub(crate) mod snapshot;
+pub(crate) mod formatters;
+pub(crate) mod diff;
+pub(crate) mod merge;
+pub(crate) mod sniff;
+pub(crate) mod store;
+pub(crate) mod verify;
+pub(crate) mod watch;
+
+mod api;
+
+#[doc(hidden)]
+pub mod cli;
+
+pub use api::{Rendered, Snapshot, SnapshotBuilder};
+pub use error::{Error, Result};
+pub use snapshot::options::{Format, SnapshotOptions};
+pub use snapshot::run::{Shard, Sniffed};
+pub use types::FileEntry;
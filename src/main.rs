@@ -1,15 +1,21 @@
 //! main.rs — with args.rs fully absorbed
+//!
+//! Thin wrapper over the `yggdrasil_cli` library: parse flags, resolve the
+//! CLI-only shortcuts (--printed, --whited, pattern files, stdin prompts) into
+//! `SnapshotOptions`, dispatch, and map errors to exit codes.
 
-mod types;
-mod snapshot;
-mod scanner;
-mod formatters;
-mod diff;
-mod sniff;
+use std::io;
 
 use clap::{Parser, Subcommand, CommandFactory};
-use snapshot::run_snapshot;
-use diff::run_diff;
+use atty::Stream;
+
+use yggdrasil_cli::{Format, SnapshotOptions};
+use yggdrasil_cli::cli::{
+    announce_sniff, load_patterns_file, print_log, print_merge_report, read_multiline_stdin,
+    run_cache, run_diff, run_log, run_merge, run_show, run_snap, run_snapshot, run_verify,
+    run_watch, DiffAlgorithm, DiffFormat, DiffOptions, Emphasis, FailOn, HunkOptions, MatchMode,
+    MergeOptions, MergedFile, Selection,
+};
 
 
 #[derive(Parser, Debug)]
@@ -32,26 +38,187 @@ pub struct Cli {
 pub enum Commands {
     /// Compare two sets of files (original vs modified)
     Diff {
-        /// Source files or directories
-        #[arg(required = true)]
+        /// Source files or directories (with --rev: paths to limit the diff to)
+        #[arg(required_unless_present_any = ["codex", "rev"])]
         from: Vec<String>,
-        /// Target files or directories
-        #[arg(required = true)]
+        /// Target files or directories (after `--`)
+        #[arg(required_unless_present_any = ["codex", "rev"], last = true)]
         to: Vec<String>,
+        /// Compare the files embedded in two codices instead of trees on disk
+        #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with_all = ["from", "to"])]
+        codex: Option<Vec<String>>,
+        /// Read from git: `A` compares ref A with the working tree, `A..B` two refs
+        #[arg(long, value_name = "A[..B]", conflicts_with_all = ["to", "codex"])]
+        rev: Option<String>,
+        /// Only compare files with these extensions (e.g. --show py,rs)
+        #[arg(long, value_name = "EXT")]
+        show: Vec<String>,
+        /// Skip files matching these names, paths or globs (relative to each side)
+        #[arg(long, value_name = "PATTERN")]
+        ignore: Vec<String>,
+        /// Only compare files matching these names, paths or globs
+        #[arg(long, value_name = "PATTERN")]
+        only: Vec<String>,
+        /// Load --ignore patterns from a file
+        #[arg(long, alias = "blacklist", value_name = "FILE")]
+        black: Option<String>,
+        /// Load --only patterns from a file
+        #[arg(long, alias = "manifest", value_name = "FILE")]
+        white: Option<String>,
+        /// Do not skip files ignored by .gitignore when walking directories
+        #[arg(long)]
+        no_gitignore: bool,
         /// Align diff tags to a fixed column
         #[arg(long)]
         align_tags: bool,
+        /// Output format: `cli` (inline, coloured), `patch` (for `git apply`), `json`,
+        /// `html` or `md`; defaults to the `--out` extension, else `cli`
+        /// (`-y`, `--stat`, `--verify-move-only` and `--semantic` always write
+        /// their own layout)
+        #[arg(long, value_enum)]
+        format: Option<DiffFormat>,
+        /// Write the report to FILE instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<String>,
+        /// Show old and new side by side, fitted to the terminal width
+        #[arg(short = 'y', long, conflicts_with = "format")]
+        side_by_side: bool,
+        /// Summarise insertions, deletions and moved lines per file instead of
+        /// printing the diff
+        #[arg(long, conflicts_with_all = ["format", "side_by_side"])]
+        stat: bool,
+        /// Print nothing; exit 1 if the sides differ, 0 if not
+        #[arg(short = 'q', long, conflicts_with = "out")]
+        quiet: bool,
+        /// Exit 1 only for these kinds of change (comma-separated); e.g.
+        /// `--fail-on added,removed` to allow nothing but moves
+        #[arg(long, value_enum, value_delimiter = ',', value_name = "KIND")]
+        fail_on: Vec<FailOn>,
+        /// Check that every changed line was moved or reformatted; print a
+        /// verdict and the remaining changes, exit 1 if there are any
+        #[arg(long, conflicts_with_all = ["format", "side_by_side", "stat", "fail_on"])]
+        verify_move_only: bool,
+        /// Report changed functions, classes and types of Python and Rust
+        /// files (added, removed, renamed, moved, signature or body changed)
+        /// instead of changed lines
+        #[arg(long, conflicts_with_all = ["format", "side_by_side", "stat", "verify_move_only"])]
+        semantic: bool,
+        /// Browse the diff full-screen: a file list with status and moved-block
+        /// counts, and a diff pane with hunk / move navigation and jumps to
+        /// where moved lines went
+        #[arg(long, conflicts_with_all = ["format", "out", "side_by_side", "stat", "quiet", "verify_move_only", "semantic"])]
+        tui: bool,
+        /// Highlight changes inside modified lines by `word`, `char` or `none`
+        #[arg(long, value_enum, default_value_t = Emphasis::Word)]
+        emphasis: Emphasis,
+        /// Lines of context around each hunk
+        #[arg(short = 'U', long = "unified", visible_alias = "context", value_name = "N", default_value_t = 3)]
+        unified: usize,
+        /// Treat lines that differ only in whitespace as unchanged
+        #[arg(short = 'w', long)]
+        ignore_whitespace: bool,
+        /// Do not start hunks for added or removed blank lines
+        #[arg(long)]
+        ignore_blank_lines: bool,
+        /// Treat lines that differ only in letter case as unchanged
+        #[arg(long)]
+        ignore_case: bool,
+        /// Line diff algorithm: `myers`, `patience` or `lcs`
+        #[arg(long, value_enum, default_value_t = DiffAlgorithm::Myers)]
+        diff_algorithm: DiffAlgorithm,
+        /// Minimum similarity (%) to report a file as renamed or copied
+        #[arg(short = 'M', long, value_name = "N", default_value_t = 50,
+              value_parser = clap::value_parser!(u8).range(1..=100))]
+        find_renames: u8,
+        /// Report renamed files as a removal plus an addition
+        #[arg(long)]
+        no_renames: bool,
+        /// Also detect added files copied from a file that still exists
+        #[arg(short = 'C', long, conflicts_with = "no_renames")]
+        find_copies: bool,
+        /// How lines are compared when detecting moved blocks: `exact`,
+        /// `whitespace` (re-indented code) or `identifiers` (renamed variables)
+        #[arg(long, value_enum, default_value_t = MatchMode::Exact)]
+        move_match: MatchMode,
+    },
+
+    /// Check a Markdown codex against the working tree: drifted files,
+    /// missing files, and whether the codex itself was edited.
+    Verify {
+        /// Codex file produced by `--printed` / `--out *.md`
+        codex: String,
+        /// Root the codex paths are resolved against
+        #[arg(long, default_value = ".")]
+        dir: String,
+    },
+
+    /// Three-way merge two edited copies of a codex against the codex they
+    /// started from, file by file, with conflict markers where both changed
+    /// the same lines
+    Merge {
+        /// Codex both sides started from
+        base: String,
+        /// Our edited copy (named in `<<<<<<<` markers)
+        ours: String,
+        /// Their edited copy (named in `>>>>>>>` markers)
+        theirs: String,
+        /// Write the merged codex to FILE instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<String>,
+        /// Write the merged files under --dir instead of printing a codex
+        #[arg(long)]
+        apply: bool,
+        /// Root the codex paths are resolved against
+        #[arg(long, default_value = ".")]
+        dir: String,
+    },
+
+    /// Record the selected files as a snapshot in the local `.ygg` store
+    Snap {
+        /// Short note stored with the snapshot (shown by `ygg log`)
+        #[arg(long, short = 'm')]
+        message: Option<String>,
+
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List recorded snapshots, newest first
+    Log {
+        /// Root directory holding the `.ygg` store
+        #[arg(long, default_value = ".")]
+        dir: String,
+    },
+
+    /// Inspect or maintain the per-file stats cache ($XDG_CACHE_HOME/ygg)
+    Cache {
+        /// Root directory holding the `.ygg` store
+        #[arg(long, default_value = ".")]
+        dir: String,
+
+        /// Delete the cache entirely
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Re-render a recorded snapshot (accepts any unique id prefix)
+    Show {
+        /// Snapshot id or prefix, as printed by `ygg log`
+        id: String,
+
+        #[command(flatten)]
+        args: Args,
     },
 }
 
-#[derive(clap::Args, Debug)]
+#[derive(clap::Args, Debug, Clone)]
 pub struct Args {
     /// Root directory to scan
     #[arg(long, default_value = ".")]
     pub dir: String,
 
     /// Show only files with these extensions (e.g. --show tex rs md)
-    #[arg(long, num_args = 0.., value_delimiter = ' ')]
+    #[arg(long, num_args = 0..)]
     pub show: Vec<String>,
 
     /// Print file contents as well
@@ -63,7 +230,7 @@ pub struct Args {
     pub md: bool,
 
     /// Restrict output to these files/dirs/globs
-    #[arg(long, num_args = 1.., value_delimiter = ' ')]
+    #[arg(long, num_args = 1..)]
     pub only: Vec<String>,
 
     /// Do not display line counts in file index
@@ -71,7 +238,7 @@ pub struct Args {
     pub no_lines: bool,
 
     /// Provide inline patterns to ignore (globs, names, etc.)
-    #[arg(long, num_args = 1.., value_delimiter = ' ')]
+    #[arg(long, num_args = 1..)]
     pub ignore: Vec<String>,
 
     /// Load blacklist patterns (like .gitignore) or enter manually.
@@ -113,11 +280,129 @@ pub struct Args {
     ///   ygg --sniff scripts/audit.py --dir ../my-project --printed --split
     #[arg(long)]
     pub sniff: Option<String>,
+
+    /// Ignore the on-disk stats cache and re-read every file
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Keep running and regenerate the output whenever selected files change
+    /// (re-runs --sniff, so newly imported files are picked up).
+    ///
+    /// Example:
+    ///   ygg --sniff src/main.py --printed --watch
+    #[arg(long)]
+    pub watch: bool,
 }
 
 
-pub mod cli {
-    pub use super::{Cli, Args, Commands};
+impl Args {
+    /// Resolve CLI shortcuts and pattern sources into library options.
+    pub fn into_options(mut self) -> SnapshotOptions {
+
+        // Legacy: --whited
+        if let Some(opt) = &self.whited {
+            self.contents = true;
+
+            self.out = Some(match opt {
+                Some(name) => name.clone(),
+                None => "SHOW.md".to_string(),
+            });
+
+            if self.white.is_none() {
+                self.white = Some(None); // triggers stdin pattern prompt
+            }
+        }
+
+        // New: --printed
+        if let Some(opt) = &self.printed {
+            self.contents = true;
+
+            self.out = Some(match opt {
+                Some(name) => name.clone(),
+                None => "SHOW.md".to_string(),
+            });
+        }
+
+        let mut ignore_patterns = split_list(self.ignore);
+        let mut only_patterns = split_list(self.only);
+
+        // --black
+        if let Some(black_opt) = &self.black {
+            match black_opt {
+                Some(file) => ignore_patterns.extend(load_patterns_file(file)),
+                None => {
+                    if let Some(p) = read_multiline_stdin("Enter BLACK patterns (one per line):") {
+                        ignore_patterns.extend(p);
+                    }
+                }
+            }
+        }
+
+        // --white
+        if let Some(white_opt) = &self.white {
+            match white_opt {
+                Some(file) => only_patterns.extend(load_patterns_file(file)),
+                None => {
+                    if let Some(p) = read_multiline_stdin("Enter WHITE patterns (one per line):") {
+                        only_patterns.extend(p);
+                    }
+                }
+            }
+        }
+
+        let use_md = if let Some(out_file) = &self.out {
+            out_file.ends_with(".md")
+        } else {
+            self.md
+        };
+
+        let format = if use_md {
+            Format::Markdown
+        } else {
+            Format::Text { colored: self.out.is_none() && atty::is(Stream::Stdout) }
+        };
+
+        // --split only applies when writing to a file (K = thousands of tokens)
+        let split_tokens = self.out.as_ref()
+            .and(self.split)
+            .map(|k| k.unwrap_or(32))
+            .filter(|&k| k > 0)
+            .map(|k| k * 1000);
+
+        SnapshotOptions {
+            dir: self.dir,
+            show: split_list(self.show),
+            only: only_patterns,
+            ignore: ignore_patterns,
+            sniff: self.sniff,
+            contents: self.contents,
+            format,
+            show_lines: !self.no_lines,
+            split_tokens,
+            cache: !self.no_cache,
+            out: self.out,
+        }
+    }
+}
+
+
+/// Values of `--show`, `--only` and `--ignore`, in snapshots and diffs alike:
+/// separate arguments, or one argument listing several separated by spaces
+/// (`--only "src tests"`) or commas (`--only src,tests`).
+fn split_list(values: Vec<String>) -> Vec<String> {
+    values
+        .iter()
+        .flat_map(|v| v.split(|c: char| c == ',' || c.is_whitespace()))
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+
+/// Report a library error and exit with status 2.
+fn fail(e: yggdrasil_cli::Error) -> ! {
+    eprintln!("❌ {}", e);
+    std::process::exit(2);
 }
 
 
@@ -131,12 +416,108 @@ fn main() {
     }
 
     match cli.command {
-        Some(Commands::Diff { from, to, align_tags }) => {
-            run_diff(from, to, align_tags);
+        Some(Commands::Diff {
+            from, to, codex, rev, show, ignore, only, black, white, no_gitignore, align_tags, format, out, side_by_side, stat, quiet, fail_on, verify_move_only, semantic, tui, emphasis, unified, ignore_whitespace,
+            ignore_blank_lines, ignore_case, diff_algorithm, find_renames, no_renames, find_copies,
+            move_match,
+        }) => {
+            // a layout of its own (-y, --stat, --verify-move-only, --semantic)
+            // is written to --out as it is; only the plain diff takes its
+            // format from the extension
+            let own_layout = side_by_side || stat || verify_move_only || semantic;
+            let format = format
+                .or_else(|| out.as_deref().filter(|_| !own_layout).and_then(DiffFormat::from_path))
+                .unwrap_or_default();
+            let mut ignore = split_list(ignore);
+            let mut only = split_list(only);
+            ignore.extend(black.iter().flat_map(|f| load_patterns_file(f)));
+            only.extend(white.iter().flat_map(|f| load_patterns_file(f)));
+            let base = DiffOptions {
+                selection: Selection { show: split_list(show), ignore, only },
+                gitignore: !no_gitignore,
+                format,
+                out,
+                hunks: HunkOptions {
+                    context: unified,
+                    algorithm: diff_algorithm,
+                    ignore_whitespace,
+                    ignore_blank_lines,
+                    ignore_case,
+                },
+                side_by_side,
+                stat,
+                emphasis,
+                align_tags,
+                rename_threshold: (!no_renames).then_some(find_renames),
+                find_copies,
+                move_match,
+                quiet,
+                fail_on,
+                verify_move_only,
+                semantic,
+                tui,
+                ..DiffOptions::default()
+            };
+            let options = match codex {
+                Some(pair) => DiffOptions {
+                    from: vec![pair[0].clone()],
+                    to: vec![pair[1].clone()],
+                    codex: true,
+                    ..base
+                },
+                None => DiffOptions { from, to, rev, ..base },
+            };
+            match run_diff(&options) {
+                Ok(false) => {}
+                Ok(true) => std::process::exit(1),
+                Err(e) => fail(e),
+            }
+        }
+
+        Some(Commands::Verify { codex, dir }) => {
+            let verification = run_verify(&codex, &dir).unwrap_or_else(|e| fail(e));
+            verification.print(&mut io::stdout());
+            if verification.files.is_empty() {
+                eprintln!("⚠️  No <file> blocks found in '{}'.", codex);
+            }
+            if !verification.is_clean() {
+                std::process::exit(1);
+            }
+        }
+
+        Some(Commands::Merge { base, ours, theirs, out, apply, dir }) => {
+            let files = run_merge(&MergeOptions { base, ours, theirs, out, apply, dir })
+                .unwrap_or_else(|e| fail(e));
+            print_merge_report(&files, &mut io::stderr());
+            if files.iter().any(MergedFile::is_conflicted) {
+                std::process::exit(1);
+            }
+        }
+
+        Some(Commands::Snap { message, args }) => {
+            let recorded = run_snap(&args.into_options(), message).unwrap_or_else(|e| fail(e));
+            announce_sniff(recorded.sniffed.as_ref());
+            recorded.print(&mut io::stdout());
+        }
+
+        Some(Commands::Log { dir }) => {
+            print_log(&run_log(&dir), &mut io::stdout());
+        }
+
+        Some(Commands::Cache { dir, clear }) => {
+            run_cache(&dir, clear).unwrap_or_else(|e| fail(e)).print(&mut io::stdout());
+        }
+
+        Some(Commands::Show { id, args }) => {
+            run_show(&id, &args.into_options()).unwrap_or_else(|e| fail(e));
+        }
+
+        None if cli.args.watch => {
+            run_watch(&cli.args.into_options()).unwrap_or_else(|e| fail(e));
         }
 
         None => {
-            run_snapshot(cli.args);
+            run_snapshot(&cli.args.into_options()).unwrap_or_else(|e| fail(e));
         }
     }
-}
\ No newline at end of file
+}
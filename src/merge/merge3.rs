@@ -0,0 +1,137 @@
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+/// Names written after the `<<<<<<<` / `>>>>>>>` conflict markers, usually
+/// the files the two sides came from.
+#[derive(Debug, Clone, Copy)]
+pub struct Labels<'a> {
+    pub ours: &'a str,
+    pub theirs: &'a str,
+}
+
+/// Result of a three-way merge: the merged text (with conflict markers where
+/// needed) and how many conflicts it contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merged {
+    pub text: String,
+    pub conflicts: usize,
+}
+
+/// For each line of `base`, the line of `other` it survives as, if any.
+fn kept_lines(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut kept = vec![None; base.len()];
+    for op in capture_diff_slices(Algorithm::Myers, base, other) {
+        if let DiffOp::Equal { old_index, new_index, len } = op {
+            for k in 0..len {
+                kept[old_index + k] = Some(new_index + k);
+            }
+        }
+    }
+    kept
+}
+
+impl Merged {
+    fn push_lines(&mut self, lines: &[&str]) {
+        lines.iter().for_each(|l| self.text.push_str(l));
+    }
+
+    /// Append a marker line, on a line of its own even when the last line
+    /// had no newline.
+    fn push_marker(&mut self, marker: &str) {
+        if !self.text.is_empty() && !self.text.ends_with('\n') {
+            self.text.push('\n');
+        }
+        self.text.push_str(marker);
+        self.text.push('\n');
+    }
+
+    /// Resolve one unstable region: take the side that changed it, or the
+    /// shared result if both made the same change; otherwise conflict.
+    fn push_chunk(&mut self, base: &[&str], ours: &[&str], theirs: &[&str], labels: &Labels) {
+        if ours == theirs || theirs == base {
+            self.push_lines(ours);
+        } else if ours == base {
+            self.push_lines(theirs);
+        } else {
+            self.conflicts += 1;
+            self.push_marker(&format!("<<<<<<< {}", labels.ours));
+            self.push_lines(ours);
+            self.push_marker("=======");
+            self.push_lines(theirs);
+            self.push_marker(&format!(">>>>>>> {}", labels.theirs));
+        }
+    }
+}
+
+/// Line-based three-way merge (as `git merge-file` / diff3).
+///
+/// Base lines kept by both sides are stable anchors; each region between
+/// two anchors is taken from whichever side changed it. Regions both sides
+/// changed differently become conflicts, so edits on adjacent lines conflict
+/// as they do in git.
+pub fn merge3(base: &str, ours: &str, theirs: &str, labels: &Labels) -> Merged {
+    let [base, ours, theirs] = [base, ours, theirs].map(|text| text.split_inclusive('\n').collect::<Vec<_>>());
+    let (in_ours, in_theirs) = (kept_lines(&base, &ours), kept_lines(&base, &theirs));
+
+    let mut merged = Merged { text: String::new(), conflicts: 0 };
+    let (mut b, mut o, mut t) = (0, 0, 0);
+
+    loop {
+        let anchor = (b..base.len()).find_map(|k| Some((k, in_ours[k]?, in_theirs[k]?)));
+        let (bk, ok, tk) = anchor.unwrap_or((base.len(), ours.len(), theirs.len()));
+
+        merged.push_chunk(&base[b..bk], &ours[o..ok], &theirs[t..tk], labels);
+        if anchor.is_none() {
+            return merged;
+        }
+
+        merged.text.push_str(base[bk]);
+        (b, o, t) = (bk + 1, ok + 1, tk + 1);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LABELS: Labels = Labels { ours: "ours.md", theirs: "theirs.md" };
+
+    #[test]
+    fn test_disjoint_edits_merge_cleanly() {
+        let base = "a\nb\nc\nd\ne\n";
+        let ours = "A\nb\nc\nd\ne\n";
+        let theirs = "a\nb\nc\nd\nE\nf\n";
+
+        let merged = merge3(base, ours, theirs, &LABELS);
+        assert_eq!(merged, Merged { text: "A\nb\nc\nd\nE\nf\n".into(), conflicts: 0 });
+
+        // the same edit on both sides is not a conflict
+        assert_eq!(merge3(base, ours, ours, &LABELS).text, ours);
+        // a deletion on one side is taken
+        assert_eq!(merge3(base, "a\nb\nd\ne\n", base, &LABELS).text, "a\nb\nd\ne\n");
+    }
+
+    #[test]
+    fn test_overlapping_edits_conflict() {
+        let base = "fn f() {\n    1\n}\n";
+        let ours = "fn f() {\n    2\n}\n";
+        let theirs = "fn f() {\n    3\n}\n";
+
+        let merged = merge3(base, ours, theirs, &LABELS);
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.text,
+            "fn f() {\n<<<<<<< ours.md\n    2\n=======\n    3\n>>>>>>> theirs.md\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_markers_start_on_their_own_line() {
+        let merged = merge3("x", "y", "z", &LABELS);
+        assert_eq!(merged.text, "<<<<<<< ours.md\ny\n=======\nz\n>>>>>>> theirs.md\n");
+
+        // both sides adding a file from nothing
+        let merged = merge3("", "a\n", "b\n", &LABELS);
+        assert_eq!(merged.conflicts, 1);
+    }
+}
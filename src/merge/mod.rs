@@ -0,0 +1,4 @@
+pub mod merge3;
+pub mod run;
+
+pub use run::{print_report, run_merge, MergeOptions, MergedFile};
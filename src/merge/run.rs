@@ -0,0 +1,324 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path};
+
+use colored::*;
+
+use crate::codex::integrity::content_hash;
+use crate::codex::parse::Codex;
+use crate::codex::parse_codex;
+use crate::error::{Error, Result};
+use crate::snapshot::options::{Format, SnapshotOptions};
+use crate::snapshot::run::render_shards;
+use crate::snapshot::writer::write_shards;
+use crate::store::objects::Store;
+use crate::types::FileEntry;
+
+use super::merge3::{merge3, Labels};
+
+/// Everything `run_merge` needs, resolved from the CLI.
+#[derive(Debug, Clone)]
+pub struct MergeOptions {
+    /// Codex both sides started from
+    pub base: String,
+    pub ours: String,
+    pub theirs: String,
+    /// Write the merged codex here (`None` = stdout, unless applying)
+    pub out: Option<String>,
+    /// Write merged files under `dir` instead of emitting a codex
+    pub apply: bool,
+    /// Root the codex paths are resolved against (and the `.ygg` store)
+    pub dir: String,
+}
+
+/// What happened to one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStatus {
+    /// Neither side changed it
+    Unchanged,
+    /// Changes taken from one or both sides without conflict
+    Merged,
+    /// Added by one or both sides (identically)
+    Added,
+    /// Deleted by one side and left alone by the other, or by both
+    Deleted,
+    /// Changed differently on both sides; contains this many conflicts
+    Conflicted(usize),
+    /// Deleted on one side but modified on the other; the modified version
+    /// is kept
+    DeleteModify,
+}
+
+/// One file of the merge result.
+#[derive(Debug, Clone)]
+pub struct MergedFile {
+    pub path: String,
+    /// `None` when the merge deletes the file
+    pub content: Option<String>,
+    pub status: MergeStatus,
+}
+
+impl MergedFile {
+    pub fn is_conflicted(&self) -> bool {
+        matches!(self.status, MergeStatus::Conflicted(_) | MergeStatus::DeleteModify)
+    }
+}
+
+fn content_of<'a>(codex: &'a Codex, path: &str) -> Option<&'a str> {
+    codex.files.iter().find(|f| f.path == path).map(|f| f.content.as_str())
+}
+
+/// Merge every file of three codices, in base order followed by files the
+/// sides added.
+pub fn merge_codices(base: &Codex, ours: &Codex, theirs: &Codex, labels: &Labels) -> Vec<MergedFile> {
+    let mut paths: Vec<&str> = Vec::new();
+    for f in base.files.iter().chain(&ours.files).chain(&theirs.files) {
+        if !paths.contains(&f.path.as_str()) {
+            paths.push(&f.path);
+        }
+    }
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let sides = (content_of(base, path), content_of(ours, path), content_of(theirs, path));
+            let (content, status) = match sides {
+                (Some(b), Some(o), Some(t)) if o == b && t == b => (Some(b.to_string()), MergeStatus::Unchanged),
+                (Some(b), Some(o), Some(t)) => {
+                    let merged = merge3(b, o, t, labels);
+                    let status = match merged.conflicts {
+                        0 => MergeStatus::Merged,
+                        n => MergeStatus::Conflicted(n),
+                    };
+                    (Some(merged.text), status)
+                }
+                // deleted on one side: fine unless the other side edited it
+                (Some(b), Some(kept), None) | (Some(b), None, Some(kept)) if kept == b => (None, MergeStatus::Deleted),
+                (Some(_), Some(kept), None) | (Some(_), None, Some(kept)) => {
+                    (Some(kept.to_string()), MergeStatus::DeleteModify)
+                }
+                (Some(_), None, None) => (None, MergeStatus::Deleted),
+                (None, Some(o), Some(t)) if o == t => (Some(o.to_string()), MergeStatus::Added),
+                (None, Some(o), Some(t)) => {
+                    let merged = merge3("", o, t, labels);
+                    (Some(merged.text), MergeStatus::Conflicted(merged.conflicts))
+                }
+                (None, Some(added), None) | (None, None, Some(added)) => (Some(added.to_string()), MergeStatus::Added),
+                (None, None, None) => unreachable!("path comes from one of the codices"),
+            };
+            MergedFile { path: path.to_string(), content, status }
+        })
+        .collect()
+}
+
+fn load(path: &str) -> Result<(String, Codex)> {
+    let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+    let codex = parse_codex(&text);
+    if codex.files.is_empty() {
+        return Err(Error::InvalidOption(format!(
+            "'{}' contains no embedded files (was it written with --contents?)",
+            path
+        )));
+    }
+    Ok((text, codex))
+}
+
+/// Name used in conflict markers: the codex file name without directories.
+fn label(path: &str) -> &str {
+    Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path)
+}
+
+/// The per-file summary `ygg merge` prints (to stderr, as the merged codex
+/// may be on stdout).
+pub fn print_report(files: &[MergedFile], out: &mut dyn Write) {
+    for f in files {
+        let line = match f.status {
+            MergeStatus::Unchanged => continue,
+            MergeStatus::Merged => format!("  {} {}", "✔".green(), f.path),
+            MergeStatus::Added => format!("  {} {} (added)", "+".green(), f.path),
+            MergeStatus::Deleted => format!("  {} {} (deleted)", "-".red(), f.path),
+            MergeStatus::Conflicted(n) => format!(
+                "  {} {} ({} conflict{})",
+                "✖".red().bold(),
+                f.path,
+                n,
+                if n == 1 { "" } else { "s" }
+            ),
+            MergeStatus::DeleteModify => format!(
+                "  {} {} (deleted on one side, modified on the other; modified version kept)",
+                "✖".red().bold(),
+                f.path
+            ),
+        };
+        writeln!(out, "{}", line).unwrap();
+    }
+
+    let conflicted = files.iter().filter(|f| f.is_conflicted()).count();
+    if conflicted == 0 {
+        writeln!(out, "🔀 {}", "Merged cleanly".green()).unwrap();
+    } else {
+        writeln!(
+            out,
+            "🔀 {} file{} with conflicts",
+            conflicted.to_string().red().bold(),
+            if conflicted == 1 { "" } else { "s" }
+        ).unwrap();
+    }
+}
+
+/// A codex path that stays under the root: relative, with no `..`.
+fn check_path(path: &str) -> Result<()> {
+    let escapes = Path::new(path)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes {
+        return Err(Error::InvalidOption(format!(
+            "refusing to apply '{}': codex paths must stay under --dir",
+            path
+        )));
+    }
+    Ok(())
+}
+
+/// Write merged files under `dir`; files the merge deletes are removed.
+/// Files no side changed are left alone. Nothing is written if any path
+/// would land outside `dir`.
+fn apply(files: &[MergedFile], dir: &str) -> Result<()> {
+    for f in files {
+        check_path(&f.path)?;
+    }
+
+    for f in files.iter().filter(|f| f.status != MergeStatus::Unchanged) {
+        let target = Path::new(dir).join(&f.path);
+        let shown = target.to_string_lossy().to_string();
+        match &f.content {
+            Some(content) => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).map_err(|e| Error::io(&shown, e))?;
+                }
+                fs::write(&target, content).map_err(|e| Error::io(&shown, e))?;
+            }
+            None if target.exists() => fs::remove_file(&target).map_err(|e| Error::io(&shown, e))?,
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// Render the merged files as a codex in the format of `base`. Contents go
+/// through the `.ygg` object store, as for `ygg show`.
+fn emit_codex(files: &[MergedFile], base_text: &str, options: &MergeOptions) -> Result<()> {
+    let store = Store::open(&options.dir);
+    let mut entries = Vec::new();
+
+    for f in files {
+        let Some(content) = &f.content else {
+            continue;
+        };
+        let hash = content_hash(content);
+        store.put_object(&hash, content).map_err(|e| Error::io(&f.path, e))?;
+
+        let word_count = content.split_whitespace().count();
+        entries.push(FileEntry {
+            path: f.path.clone(),
+            line_count: content.lines().count(),
+            word_count,
+            token_est: ((word_count as f32) * 1.33).round() as usize,
+            blob: Some(store.object_path(&hash).to_string_lossy().to_string()),
+            text: None,
+            hash,
+        });
+    }
+
+    let format = if base_text.contains("<<< FILE START: ") {
+        Format::Text { colored: false }
+    } else {
+        Format::Markdown
+    };
+    let snapshot = SnapshotOptions {
+        dir: options.dir.clone(),
+        contents: true,
+        format,
+        cache: false,
+        out: options.out.clone(),
+        ..SnapshotOptions::default()
+    };
+
+    write_shards(&render_shards(&snapshot, entries, None)?)
+}
+
+/// `ygg merge base ours theirs` — three-way merge two edited copies of a
+/// codex. Returns every file's outcome; see [`MergedFile::is_conflicted`].
+pub fn run_merge(options: &MergeOptions) -> Result<Vec<MergedFile>> {
+    let (base_text, base) = load(&options.base)?;
+    let (_, ours) = load(&options.ours)?;
+    let (_, theirs) = load(&options.theirs)?;
+
+    let labels = Labels { ours: label(&options.ours), theirs: label(&options.theirs) };
+    let files = merge_codices(&base, &ours, &theirs, &labels);
+
+    if options.apply {
+        apply(&files, &options.dir)?;
+    }
+    if !options.apply || options.out.is_some() {
+        emit_codex(&files, &base_text, options)?;
+    }
+
+    Ok(files)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codex::parse::CodexFile;
+
+    fn codex(files: &[(&str, &str)]) -> Codex {
+        Codex {
+            files: files
+                .iter()
+                .map(|(path, content)| CodexFile { path: path.to_string(), hash: None, content: content.to_string() })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_per_file_outcomes() {
+        let base = codex(&[("a.rs", "1\n2\n3\n"), ("gone.rs", "x\n"), ("edited.rs", "e\n"), ("same.rs", "s\n")]);
+        let ours = codex(&[("a.rs", "one\n2\n3\n"), ("edited.rs", "E\n"), ("same.rs", "s\n"), ("new.rs", "n\n")]);
+        let theirs = codex(&[("a.rs", "1\n2\nthree\n"), ("gone.rs", "x\n"), ("same.rs", "s\n")]);
+
+        let files = merge_codices(&base, &ours, &theirs, &Labels { ours: "o", theirs: "t" });
+        let by_path = |p: &str| files.iter().find(|f| f.path == p).unwrap();
+
+        assert_eq!(by_path("a.rs").status, MergeStatus::Merged);
+        assert_eq!(by_path("a.rs").content.as_deref(), Some("one\n2\nthree\n"));
+        assert_eq!(by_path("gone.rs").status, MergeStatus::Deleted);
+        assert_eq!(by_path("gone.rs").content, None);
+        assert_eq!(by_path("edited.rs").status, MergeStatus::DeleteModify);
+        assert_eq!(by_path("edited.rs").content.as_deref(), Some("E\n"));
+        assert_eq!(by_path("same.rs").status, MergeStatus::Unchanged);
+        assert_eq!(by_path("new.rs").status, MergeStatus::Added);
+
+        let order: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(order, ["a.rs", "gone.rs", "edited.rs", "same.rs", "new.rs"]);
+    }
+
+    #[test]
+    fn test_apply_rejects_paths_outside_the_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("root");
+        fs::create_dir(&dir).unwrap();
+
+        let file = |path: &str| MergedFile { path: path.into(), content: Some("x\n".into()), status: MergeStatus::Added };
+        for bad in ["../evil.rs", "src/../../evil.rs", "/tmp/evil.rs"] {
+            let result = apply(&[file("ok.rs"), file(bad)], dir.to_str().unwrap());
+            assert!(matches!(result, Err(Error::InvalidOption(_))), "{}", bad);
+        }
+        assert!(!tmp.path().join("evil.rs").exists());
+        assert!(!dir.join("ok.rs").exists());
+
+        apply(&[file("./src/ok.rs")], dir.to_str().unwrap()).unwrap();
+        assert!(dir.join("src/ok.rs").is_file());
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::codex::integrity::content_hash;
+use crate::error::{Error, Result};
+
+const HEADER: &str = "# ygg stats cache v2";
+
+/// Per-file stats remembered between runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedStats {
+    pub mtime_ns: u128,
+    pub size: u64,
+    pub hash: String,
+    pub line_count: usize,
+    pub word_count: usize,
+    pub token_est: usize,
+}
+
+/// On-disk cache of file stats, kept outside the project in the user's
+/// cache directory (see [`StatsCache::cache_file`]).
+///
+/// Entries are keyed on path relative to `--dir`, so the cache stays valid
+/// whatever directory ygg runs from, and trusted while mtime and size match.
+/// When they don't, the file is re-read and re-hashed; if the hash is
+/// unchanged (e.g. a `touch` or checkout) the counts are reused and only the
+/// key is refreshed.
+pub struct StatsCache {
+    root: PathBuf,
+    file: Option<PathBuf>,
+    entries: HashMap<String, CachedStats>,
+    dirty: bool,
+}
+
+/// mtime (ns since epoch) and size of a file, if it can be stat'ed.
+pub fn stat_key(path: &str) -> Option<(u128, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    let mtime = meta
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    Some((mtime, meta.len()))
+}
+
+fn parse_line(line: &str) -> Option<(String, CachedStats)> {
+    let cols: Vec<&str> = line.splitn(7, '\t').collect();
+    if cols.len() != 7 {
+        return None;
+    }
+    let stats = CachedStats {
+        mtime_ns: cols[0].parse().ok()?,
+        size: cols[1].parse().ok()?,
+        hash: cols[2].to_string(),
+        line_count: cols[3].parse().ok()?,
+        word_count: cols[4].parse().ok()?,
+        token_est: cols[5].parse().ok()?,
+    };
+    Some((cols[6].to_string(), stats))
+}
+
+impl StatsCache {
+    /// `$XDG_CACHE_HOME/ygg/<hash of the canonical dir>/stats`, with
+    /// `~/.cache` when `XDG_CACHE_HOME` is unset; `None` without either.
+    pub fn cache_file(dir: &str) -> Option<PathBuf> {
+        let base = env::var_os("XDG_CACHE_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+        let root = Path::new(dir).canonicalize().unwrap_or_else(|_| PathBuf::from(dir));
+        let key = content_hash(&root.to_string_lossy());
+        Some(base.join("ygg").join(&key[..16]).join("stats"))
+    }
+
+    /// A cache that never hits and never writes (`--no-cache`).
+    pub fn disabled() -> Self {
+        StatsCache { root: PathBuf::new(), file: None, entries: HashMap::new(), dirty: false }
+    }
+
+    /// The cache for `dir`; disabled when there is no cache directory.
+    pub fn load(dir: &str) -> Self {
+        match Self::cache_file(dir) {
+            Some(file) => Self::open(file, dir),
+            None => Self::disabled(),
+        }
+    }
+
+    fn open(file: PathBuf, dir: &str) -> Self {
+        let mut entries = HashMap::new();
+
+        if let Ok(text) = fs::read_to_string(&file) {
+            let mut lines = text.lines();
+            if lines.next() == Some(HEADER) {
+                entries.extend(lines.filter_map(parse_line));
+            }
+        }
+
+        StatsCache { root: PathBuf::from(dir), file: Some(file), entries, dirty: false }
+    }
+
+    /// Key for a path as found by the walk (`<dir>/src/a.rs` → `src/a.rs`).
+    fn key(&self, path: &str) -> String {
+        Path::new(path)
+            .strip_prefix(&self.root)
+            .map(|rel| rel.to_string_lossy().to_string())
+            .unwrap_or_else(|_| path.to_string())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Entry for `path` whose mtime and size still match.
+    pub fn fresh(&self, path: &str, mtime_ns: u128, size: u64) -> Option<&CachedStats> {
+        self.entries
+            .get(&self.key(path))
+            .filter(|s| s.mtime_ns == mtime_ns && s.size == size)
+    }
+
+    /// Entry for `path` regardless of freshness (for hash validation).
+    pub fn get(&self, path: &str) -> Option<&CachedStats> {
+        self.entries.get(&self.key(path))
+    }
+
+    pub fn insert(&mut self, path: &str, stats: CachedStats) {
+        if self.file.is_none() {
+            return;
+        }
+        let key = self.key(path);
+        if self.entries.get(&key) != Some(&stats) {
+            self.entries.insert(key, stats);
+            self.dirty = true;
+        }
+    }
+
+    /// Drop entries whose files no longer exist. Returns how many were removed.
+    pub fn prune(&mut self) -> usize {
+        let before = self.entries.len();
+        let root = &self.root;
+        self.entries.retain(|key, _| root.join(key).is_file());
+        let removed = before - self.entries.len();
+        if removed > 0 {
+            self.dirty = true;
+        }
+        removed
+    }
+
+    /// Forget everything and delete the cache file.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.dirty = false;
+        if let Some(file) = &self.file {
+            let _ = fs::remove_file(file);
+        }
+    }
+
+    /// Persist if anything changed (stale entries are pruned first).
+    pub fn save(&mut self) -> io::Result<()> {
+        let Some(file) = self.file.clone() else { return Ok(()) };
+        if !self.dirty {
+            return Ok(());
+        }
+
+        self.prune();
+
+        let mut paths: Vec<&String> = self.entries.keys().collect();
+        paths.sort();
+
+        let mut text = format!("{}\n", HEADER);
+        for path in paths {
+            let s = &self.entries[path];
+            text.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                s.mtime_ns, s.size, s.hash, s.line_count, s.word_count, s.token_est, path
+            ));
+        }
+
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&file, text)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// What `ygg cache` did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheReport {
+    /// Neither `$XDG_CACHE_HOME` nor `$HOME` is set
+    Unavailable,
+    Cleared { file: PathBuf, entries: usize },
+    Pruned { file: PathBuf, entries: usize, pruned: usize },
+}
+
+impl CacheReport {
+    /// The summary line `ygg cache` prints.
+    pub fn print(&self, out: &mut dyn Write) {
+        match self {
+            CacheReport::Unavailable => {
+                writeln!(out, "🗃️  No stats cache: neither $XDG_CACHE_HOME nor $HOME is set").unwrap()
+            }
+            CacheReport::Cleared { file, entries } => {
+                writeln!(out, "🧹 Cleared stats cache ({} entries) at {}", entries, file.display()).unwrap()
+            }
+            CacheReport::Pruned { file, entries, pruned } => writeln!(
+                out,
+                "🗃️  Stats cache {}: {} entries ({} stale pruned)",
+                file.display(),
+                entries,
+                pruned
+            ).unwrap(),
+        }
+    }
+}
+
+/// `ygg cache` — prune entries for deleted files, or `--clear` everything.
+pub fn run_cache(dir: &str, clear: bool) -> Result<CacheReport> {
+    let Some(file) = StatsCache::cache_file(dir) else {
+        return Ok(CacheReport::Unavailable);
+    };
+    let mut cache = StatsCache::open(file.clone(), dir);
+
+    if clear {
+        let entries = cache.len();
+        cache.clear();
+        return Ok(CacheReport::Cleared { file, entries });
+    }
+
+    let pruned = cache.prune();
+    cache.save().map_err(|e| Error::io(file.display().to_string(), e))?;
+    Ok(CacheReport::Pruned { file, entries: cache.len(), pruned })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(mtime_ns: u128, hash: &str) -> CachedStats {
+        CachedStats {
+            mtime_ns,
+            size: 10,
+            hash: hash.into(),
+            line_count: 2,
+            word_count: 4,
+            token_est: 5,
+        }
+    }
+
+    #[test]
+    fn test_cache_roundtrip_and_freshness() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_str().unwrap();
+        let tracked = tmp.path().join("a.rs");
+        fs::write(&tracked, "fn a() {}\n").unwrap();
+        let key = tracked.to_str().unwrap();
+
+        let file = tmp.path().join("cache/stats");
+        let mut cache = StatsCache::open(file.clone(), dir);
+        assert_eq!(cache.len(), 0);
+        cache.insert(key, stats(42, "abc"));
+        cache.insert("gone.rs", stats(1, "def"));
+        cache.save().unwrap();
+
+        let reloaded = StatsCache::open(file.clone(), dir);
+        assert_eq!(reloaded.len(), 1); // gone.rs pruned on save
+        assert!(reloaded.fresh(key, 42, 10).is_some());
+        assert!(reloaded.fresh(key, 43, 10).is_none());
+        assert_eq!(reloaded.get(key).unwrap().hash, "abc");
+
+        let text = fs::read_to_string(&file).unwrap();
+        assert!(text.ends_with("\ta.rs\n"));
+    }
+
+    #[test]
+    fn test_prune_resolves_keys_against_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_str().unwrap();
+        fs::create_dir_all(tmp.path().join("pkg")).unwrap();
+        fs::write(tmp.path().join("pkg/mod.rs"), "").unwrap();
+
+        let file = tmp.path().join("cache/stats");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, format!("{}\n1\t10\tabc\t2\t4\t5\tpkg/mod.rs\n1\t10\tdef\t2\t4\t5\tpkg/gone.rs\n", HEADER)).unwrap();
+
+        // pkg/ does not exist under the test's working directory
+        let mut cache = StatsCache::open(file, dir);
+        assert_eq!(cache.prune(), 1);
+        assert!(cache.get(tmp.path().join("pkg/mod.rs").to_str().unwrap()).is_some());
+    }
+
+    #[test]
+    fn test_disabled_cache_never_writes() {
+        let mut cache = StatsCache::disabled();
+        cache.insert("a.rs", stats(1, "abc"));
+        cache.save().unwrap();
+        assert_eq!(cache.len(), 0);
+    }
+}
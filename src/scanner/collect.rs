@@ -1,90 +1,95 @@
 use walkdir::WalkDir;
 
-use crate::cli::Args;
+use crate::snapshot::options::SnapshotOptions;
+use crate::codex::integrity::content_hash;
+use crate::store::objects::STORE_DIR;
 use crate::types::FileEntry;
 
-use super::stdin::read_multiline_stdin;
-use super::patterns::load_patterns_file;
-use super::filters::matches_filters;
+use super::selection::Selection;
+use super::cache::{stat_key, CachedStats, StatsCache};
 
 use std::fs;
 
 /// Collect all file paths according to ignore/only filters and flags.
-pub fn collect_files(args: &Args) -> Vec<FileEntry> {
-
-    let mut ignore_patterns = args.ignore.clone();
-    let mut only_patterns = args.only.clone();
-
-    // --black
-    if let Some(black_opt) = &args.black {
-        match black_opt {
-            Some(file) => ignore_patterns.extend(load_patterns_file(file)),
-            None => {
-                if let Some(p) = read_multiline_stdin("Enter BLACK patterns (one per line):") {
-                    ignore_patterns.extend(p);
-                }
-            }
-        }
-    }
+pub fn collect_files(options: &SnapshotOptions) -> Vec<FileEntry> {
 
-    // --white
-    if let Some(white_opt) = &args.white {
-        match white_opt {
-            Some(file) => only_patterns.extend(load_patterns_file(file)),
-            None => {
-                if let Some(p) = read_multiline_stdin("Enter WHITE patterns (one per line):") {
-                    only_patterns.extend(p);
-                }
-            }
-        }
-    }
+    let selection = Selection::from_options(options);
 
     let mut files = Vec::new();
 
-    // Walk directory tree
-    for entry in WalkDir::new(&args.dir).into_iter().filter_map(|e| e.ok()) {
+    let mut cache = if options.cache {
+        StatsCache::load(&options.dir)
+    } else {
+        StatsCache::disabled()
+    };
 
-        if entry.file_type().is_file() {
+    // Walk directory tree (never descend into the .ygg store)
+    let walker = WalkDir::new(&options.dir)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != STORE_DIR);
 
-            let path = entry.path().to_string_lossy().to_string();
+    for entry in walker.filter_map(|e| e.ok()) {
 
-            // --show <ext>
-            if !args.show.is_empty() {
-                let ext = entry.path()
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("");
+        if entry.file_type().is_file() {
 
-                if !args.show.contains(&ext.to_string()) {
-                    continue;
-                }
-            }
+            let path = entry.path().to_string_lossy().to_string();
 
-            // ignore filters
-            if matches_filters(&path, &ignore_patterns, false) {
+            if !selection.accepts(&path) {
                 continue;
             }
 
-            // only filters
-            if !matches_filters(&path, &only_patterns, true) {
-                continue;
-            }
+            files.push(file_entry(path, &mut cache));
+        }
+    }
+
+    // the cache only saves work; failing to persist it must not fail the scan
+    let _ = cache.save();
+
+    files
+}
+
+/// Index entry for one file, its stats served from `cache` when still fresh.
+pub fn file_entry(path: String, cache: &mut StatsCache) -> FileEntry {
+    let stats = file_stats(&path, cache);
+
+    FileEntry {
+        path,
+        line_count: stats.line_count,
+        word_count: stats.word_count,
+        token_est: stats.token_est,
+        hash: stats.hash,
+        blob: None,
+        text: None,
+    }
+}
 
-            // Read file once
-            let contents = fs::read_to_string(&path).unwrap_or_default();
+/// Stats for one file, served from the cache when mtime/size still match.
+fn file_stats(path: &str, cache: &mut StatsCache) -> CachedStats {
+    let key = stat_key(path);
 
+    if let Some((mtime_ns, size)) = key {
+        if let Some(hit) = cache.fresh(path, mtime_ns, size) {
+            return hit.clone();
+        }
+    }
+
+    // Read file once
+    let contents = fs::read_to_string(path).unwrap_or_default();
+    let hash = content_hash(&contents);
+    let (mtime_ns, size) = key.unwrap_or((0, 0));
+
+    // Same bytes under a new mtime (touch, checkout): keep the counts
+    let stats = match cache.get(path).filter(|s| s.hash == hash) {
+        Some(prev) => CachedStats { mtime_ns, size, ..prev.clone() },
+        None => {
             let line_count = contents.lines().count();
             let word_count = contents.split_whitespace().count();
             let token_est = ((word_count as f32) * 1.33).round() as usize;
 
-            files.push(FileEntry {
-                path,
-                line_count,
-                word_count,
-                token_est,
-            });
+            CachedStats { mtime_ns, size, hash, line_count, word_count, token_est }
         }
-    }
+    };
 
-    files
+    cache.insert(path, stats.clone());
+    stats
 }
\ No newline at end of file
@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+/// One pattern line of a `.gitignore`.
+#[derive(Debug, Clone)]
+struct Rule {
+    glob: Pattern,
+    /// `!pattern`: re-include what an earlier rule ignored
+    negate: bool,
+    /// `pattern/`: only matches directories
+    dir_only: bool,
+    /// Contains a `/` other than a trailing one: matched against the path
+    /// relative to the `.gitignore`, not against any file name
+    anchored: bool,
+}
+
+/// Parse `.gitignore` text. Supports comments, `!` negation, `\#` / `\!` for
+/// a literal leading `#` / `!`, trailing-`/` directory patterns, anchored
+/// patterns and globs (including `**`). Escaped trailing spaces (`\ `) are
+/// not supported: trailing whitespace is always trimmed.
+fn parse_rules(text: &str) -> Vec<Rule> {
+    text.lines()
+        .map(str::trim_end)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|line| {
+            let (negate, line) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let line = line
+                .strip_prefix('\\')
+                .filter(|rest| rest.starts_with(['#', '!']))
+                .unwrap_or(line);
+            let (dir_only, line) = match line.strip_suffix('/') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let anchored = line.contains('/');
+            let glob = Pattern::new(line.trim_start_matches('/')).ok()?;
+            Some(Rule { glob, negate, dir_only, anchored })
+        })
+        .collect()
+}
+
+/// `.gitignore` rules in effect during a directory walk, each file scoped to
+/// the directory it was found in.
+#[derive(Debug, Clone, Default)]
+pub struct GitIgnore {
+    files: Vec<(PathBuf, Vec<Rule>)>,
+}
+
+impl GitIgnore {
+    /// Rules from the `.gitignore` files above `root`, up to the enclosing
+    /// git work tree (none when `root` is not inside one, or is its top).
+    /// Those in `root` and below are added with [`GitIgnore::add_dir`] as
+    /// the walk reaches them.
+    pub fn for_root(root: &Path) -> Self {
+        let mut ignore = GitIgnore::default();
+        let Ok(root) = root.canonicalize() else {
+            return ignore;
+        };
+
+        let ancestors: Vec<&Path> = root.ancestors().collect();
+        if let Some(top) = ancestors.iter().position(|dir| dir.join(".git").exists()) {
+            for dir in ancestors[1..=top].iter().rev() {
+                ignore.add_dir(dir);
+            }
+        }
+        ignore
+    }
+
+    /// Load `dir/.gitignore`, if any. `dir` must be canonical.
+    pub fn add_dir(&mut self, dir: &Path) {
+        if let Ok(text) = fs::read_to_string(dir.join(".gitignore")) {
+            self.files.push((dir.to_path_buf(), parse_rules(&text)));
+        }
+    }
+
+    /// Whether the canonical `path` is ignored; the last matching rule wins,
+    /// deeper `.gitignore` files over shallower ones.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let separator = MatchOptions { require_literal_separator: true, ..MatchOptions::new() };
+
+        let mut ignored = false;
+        for (base, rules) in &self.files {
+            let Ok(rel) = path.strip_prefix(base) else {
+                continue;
+            };
+            let rel = rel.to_string_lossy().replace('\\', "/");
+
+            for rule in rules {
+                if rule.dir_only && !is_dir {
+                    continue;
+                }
+                let hit = if rule.anchored {
+                    rule.glob.matches_with(&rel, separator)
+                } else {
+                    rule.glob.matches(name)
+                };
+                if hit {
+                    ignored = !rule.negate;
+                }
+            }
+        }
+        ignored
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gitignore_rules() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("pkg")).unwrap();
+        fs::write(root.join(".gitignore"), "# build\n__pycache__/\n*.log\n!keep.log\n/dist\ndocs/*.tmp\n").unwrap();
+
+        let mut ignore = GitIgnore::for_root(&root.join("pkg"));
+        ignore.add_dir(&root.join("pkg"));
+
+        assert!(ignore.is_ignored(&root.join("pkg/__pycache__"), true));
+        assert!(!ignore.is_ignored(&root.join("pkg/__pycache__"), false));
+        assert!(ignore.is_ignored(&root.join("pkg/debug.log"), false));
+        assert!(!ignore.is_ignored(&root.join("keep.log"), false));
+        assert!(ignore.is_ignored(&root.join("dist"), true));
+        assert!(!ignore.is_ignored(&root.join("pkg/dist"), true));
+        assert!(ignore.is_ignored(&root.join("docs/a.tmp"), false));
+        assert!(!ignore.is_ignored(&root.join("docs/sub/a.tmp"), false));
+        assert!(!ignore.is_ignored(&root.join("pkg/main.py"), false));
+    }
+
+    #[test]
+    fn test_work_tree_root_stops_the_search() {
+        let tmp = tempfile::tempdir().unwrap();
+        let outer = tmp.path().canonicalize().unwrap();
+        let repo = outer.join("repo");
+        fs::create_dir_all(outer.join(".git")).unwrap();
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::write(outer.join(".gitignore"), "repo/\n*.py\n").unwrap();
+
+        let ignore = GitIgnore::for_root(&repo);
+        assert!(!ignore.is_ignored(&repo.join("main.py"), false));
+    }
+
+    #[test]
+    fn test_escaped_hash_and_bang() {
+        let rules = parse_rules("\\#notes.txt\n\\!*.txt\n!\\!keep.txt\n");
+        let ignore = GitIgnore { files: vec![(PathBuf::from("/r"), rules)] };
+        assert!(ignore.is_ignored(Path::new("/r/#notes.txt"), false));
+        assert!(ignore.is_ignored(Path::new("/r/!important.txt"), false));
+        assert!(!ignore.is_ignored(Path::new("/r/!keep.txt"), false));
+        assert!(!ignore.is_ignored(Path::new("/r/important.txt"), false));
+    }
+}
@@ -1,7 +1,11 @@
+pub mod cache;
 pub mod collect;
 pub mod filters;
+pub mod gitignore;
 pub mod patterns;
+pub mod selection;
 pub mod stdin;
 
 pub use collect::collect_files;
+pub use cache::run_cache;
 
@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use crate::snapshot::options::SnapshotOptions;
+
+use super::filters::matches_filters;
+
+/// Resolved selection rules: `--show` extensions plus ignore/only patterns
+/// (the CLI merges `--black` / `--white` pattern files into these).
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    pub show: Vec<String>,
+    pub ignore: Vec<String>,
+    pub only: Vec<String>,
+}
+
+impl Selection {
+    pub fn from_options(options: &SnapshotOptions) -> Self {
+        Selection {
+            show: options.show.clone(),
+            ignore: options.ignore.clone(),
+            only: options.only.clone(),
+        }
+    }
+
+    /// Does `path` pass the --show, ignore and only filters?
+    pub fn accepts(&self, path: &str) -> bool {
+        // --show <ext>
+        if !self.show.is_empty() {
+            let ext = Path::new(path)
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("");
+
+            if !self.show.iter().any(|s| s == ext) {
+                return false;
+            }
+        }
+
+        // ignore filters
+        if matches_filters(path, &self.ignore, false) {
+            return false;
+        }
+
+        // only filters
+        matches_filters(path, &self.only, true)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_applies_show_ignore_only() {
+        let sel = Selection {
+            show: vec!["rs".into()],
+            ignore: vec!["target".into()],
+            only: vec!["src".into()],
+        };
+
+        assert!(sel.accepts("./src/main.rs"));
+        assert!(!sel.accepts("./src/notes.md"));
+        assert!(!sel.accepts("./target/debug/build.rs"));
+        assert!(!sel.accepts("./tests/cli.rs"));
+    }
+}
@@ -1,25 +1,14 @@
-use crate::cli::Args;
 use crate::formatters::{CliFormatter, MarkdownFormatter};
-
 use crate::formatters::traits::OutputFormatter;
-use atty::Stream;
+
+use super::options::Format;
 
 pub fn select_formatter<'a>(
-    args: &Args,
+    format: Format,
+    show_lines: bool,
 ) -> Box<dyn OutputFormatter + 'a> {
-
-    let use_md = if let Some(out_file) = &args.out {
-        out_file.ends_with(".md")
-    } else {
-        args.md
-    };
-
-    if use_md {
-        Box::new(MarkdownFormatter { show_lines: !args.no_lines })
-    } else {
-        Box::new(CliFormatter {
-            colored: args.out.is_none() && atty::is(Stream::Stdout),
-        })
+    match format {
+        Format::Markdown => Box::new(MarkdownFormatter { show_lines }),
+        Format::Text { colored } => Box::new(CliFormatter { colored }),
     }
 }
-
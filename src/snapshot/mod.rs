@@ -1,3 +1,4 @@
+pub mod options;
 pub mod run;
 pub mod filelist;
 pub mod writer;
@@ -0,0 +1,59 @@
+/// Output format for a rendered snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `# CODEX` document with `<file>` blocks, stats and a seal.
+    Markdown,
+    /// Plain-text index and `<<< FILE START >>>` markers.
+    Text {
+        /// Emit ANSI colors (only sensible for a terminal)
+        colored: bool,
+    },
+}
+
+/// Everything a snapshot needs, independent of how it was requested.
+///
+/// The CLI builds this from its flags (resolving `--black` / `--white`
+/// files, `--printed`, etc.); library users get one from
+/// [`Snapshot::builder`](crate::Snapshot::builder).
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+    /// Root directory to scan
+    pub dir: String,
+    /// Only files with these extensions
+    pub show: Vec<String>,
+    /// Restrict to these files/dirs/globs
+    pub only: Vec<String>,
+    /// Patterns to skip
+    pub ignore: Vec<String>,
+    /// Entry file to expand into its local import closure
+    pub sniff: Option<String>,
+    /// Include file contents, not just the index
+    pub contents: bool,
+    pub format: Format,
+    /// Line counts in the index (Markdown)
+    pub show_lines: bool,
+    /// Split into packets of roughly this many tokens
+    pub split_tokens: Option<usize>,
+    /// Use the per-user stats cache (see `StatsCache::cache_file`)
+    pub cache: bool,
+    /// Where the CLI writes the result (`None` = stdout)
+    pub out: Option<String>,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        SnapshotOptions {
+            dir: ".".to_string(),
+            show: Vec::new(),
+            only: Vec::new(),
+            ignore: Vec::new(),
+            sniff: None,
+            contents: false,
+            format: Format::Text { colored: false },
+            show_lines: true,
+            split_tokens: None,
+            cache: true,
+            out: None,
+        }
+    }
+}
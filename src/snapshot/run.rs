@@ -1,17 +1,36 @@
-use crate::cli::Args;
+use crate::error::{Error, Result};
 use crate::scanner::collect_files;
 use crate::snapshot::filelist::prepare_file_list;
-use crate::snapshot::writer::{open_writer, OutputTarget};
 use crate::snapshot::format_selection::select_formatter;
+use crate::snapshot::options::{Format, SnapshotOptions};
 use crate::snapshot::split::split_files_by_tokens;
 use crate::sniff::sniff_forward_paths;
+use crate::types::FileEntry;
+use crate::codex::integrity::{content_hash, seal_codex};
+use crate::snapshot::writer::write_shards;
+
+/// Files discovered by `--sniff`, for the header block.
+#[derive(Debug, Clone)]
+pub struct Sniffed {
+    pub entry: String,
+    pub paths: Vec<String>,
+}
 
-use std::fs::File;
-use std::io::Write;
-use atty;
+/// One rendered output document (the whole codex unless split).
+#[derive(Debug, Clone)]
+pub struct Shard {
+    /// 1-based shard number
+    pub index: usize,
+    pub total: usize,
+    pub files: Vec<FileEntry>,
+    pub bytes: Vec<u8>,
+    /// Destination when an output file was requested
+    pub path: Option<String>,
+}
 
-/// Inject FUR-style stats into the markdown buffer
-fn finalize_markdown(buf: &[u8], out_path: &str, shard_idx: Option<(usize, usize)>) {
+/// Inject FUR-style stats into the markdown buffer and seal it with a
+/// whole-codex hash (see `ygg verify`).
+fn finalize_markdown(buf: &[u8], shard_idx: Option<(usize, usize)>) -> String {
     let text = String::from_utf8_lossy(buf);
 
     let word_count = text.split_whitespace().count();
@@ -28,11 +47,7 @@ fn finalize_markdown(buf: &[u8], out_path: &str, shard_idx: Option<(usize, usize
         token_est
     );
 
-    let final_text = text.replacen("## INDEX", &inject, 1);
-
-    let mut file = File::create(out_path)
-        .expect("Failed to write final markdown file");
-    file.write_all(final_text.as_bytes()).unwrap();
+    seal_codex(&text.replacen("## INDEX", &inject, 1))
 }
 
 
@@ -103,176 +118,153 @@ fn write_sniff_header(
     }
 }
 
-/// Run the project snapshot (default command)
-pub fn run_snapshot(mut args: Args) {
-
-    //
-    // ============================================================
-    // 0. HANDLE --sniff
-    //    Expand entry file → reachable local deps → populate args.only
-    //    Must run before all other flag handling so downstream logic
-    //    (--ignore, --show, --split, etc.) applies normally.
-    // ============================================================
-    //
-
-    // sniff_meta: Some((entry_file, discovered_paths)) when --sniff was used.
-    // Stored here so we can emit a header block into the snapshot in step 3.
-    let sniff_meta: Option<(String, Vec<String>)> = if let Some(ref target) = args.sniff.clone() {
-        let discovered = sniff_forward_paths(target, &args.dir);
-
-        if discovered.is_empty() {
-            eprintln!(
-                "⚠️  Yggdrasil could not trace the branches of '{}'\nunder root '{}'. Verify paths and --dir.",
-                target, args.dir
-            );
-        } else {
-            eprintln!("🌿 {} branches traced from the root.", discovered.len());
-        }
-
-        // Merge with any explicit --only the user also passed.
-        // sniff paths go first so they appear before any manual additions.
-        let mut merged = discovered.clone();
-        merged.extend(args.only.drain(..));
+/// Expand `--sniff` into `options.only`.
+///
+/// Expand entry file → reachable local deps → populate options.only.
+/// Must run before all other selection so downstream logic
+/// (--ignore, --show, --split, etc.) applies normally.
+pub fn apply_sniff(options: &mut SnapshotOptions) -> Result<Option<Sniffed>> {
+    let Some(target) = options.sniff.clone() else {
+        return Ok(None);
+    };
 
-        // Deduplicate while preserving order
-        let mut seen = std::collections::HashSet::new();
-        args.only = merged
-            .into_iter()
-            .filter(|p| seen.insert(p.clone()))
-            .collect();
+    let discovered = sniff_forward_paths(&target, &options.dir);
 
-        if discovered.is_empty() { None } else { Some((target.clone(), discovered)) }
-    } else {
-        None
-    };
+    if discovered.is_empty() {
+        return Err(Error::SniffEntry { entry: target, root: options.dir.clone() });
+    }
 
-    //
-    // ============================================================
-    // 1. HANDLE --whited (legacy) AND --printed (new)
-    // ============================================================
-    //
+    // Merge with any explicit --only the user also passed.
+    // sniff paths go first so they appear before any manual additions.
+    let mut merged = discovered.clone();
+    merged.append(&mut options.only);
 
-    // Legacy: --whited
-    if let Some(opt) = &args.whited {
-        args.contents = true;
+    // Deduplicate while preserving order
+    let mut seen = std::collections::HashSet::new();
+    options.only = merged
+        .into_iter()
+        .filter(|p| seen.insert(p.clone()))
+        .collect();
 
-        args.out = Some(match opt {
-            Some(name) => name.clone(),
-            None => "SHOW.md".to_string(),
-        });
+    Ok(Some(Sniffed { entry: target, paths: discovered }))
+}
 
-        if args.white.is_none() {
-            args.white = Some(None); // triggers stdin pattern prompt
-        }
+/// Progress line for the CLI commands that trace `--sniff`.
+pub fn announce_sniff(sniffed: Option<&Sniffed>) {
+    if let Some(s) = sniffed {
+        eprintln!("🌿 {} branches traced from the root.", s.paths.len());
     }
+}
 
-    // New: --printed
-    if let Some(opt) = &args.printed {
-        args.contents = true;
-
-        args.out = Some(match opt {
-            Some(name) => name.clone(),
-            None => "SHOW.md".to_string(),
-        });
+/// Run sniff expansion and the scan: the deterministic file list for `options`.
+pub fn select_files(options: &SnapshotOptions) -> Result<(Vec<FileEntry>, Option<Sniffed>)> {
+    if !std::path::Path::new(&options.dir).is_dir() {
+        return Err(Error::RootNotFound(options.dir.clone()));
     }
 
-    //
-    // ============================================================
-    // 2. Run scan
-    // ============================================================
-    //
+    let mut options = options.clone();
+    let sniffed = apply_sniff(&mut options)?;
 
-    let root = args.dir.clone();
-    let mut writer = open_writer(&args);
+    let files = collect_files(&options);
+    Ok((prepare_file_list(files), sniffed))
+}
 
-    let files = collect_files(&args);
-    let prepared = prepare_file_list(files);
+/// Read each file once for embedding and re-hash it from those bytes, so the
+/// `hash=` tags and index match the text actually written; the scan's
+/// (possibly cached) stats still drive counts and sorting.
+pub fn load_contents<'a>(files: impl IntoIterator<Item = &'a mut FileEntry>) -> Result<()> {
+    for entry in files {
+        let text = entry.read_contents().map_err(|e| Error::io(&entry.path, e))?;
+        entry.hash = content_hash(&text);
+        entry.text = Some(text);
+    }
+    Ok(())
+}
 
-    let fmt = select_formatter(&args);
+/// Render an already-selected file list into one shard, or several with
+/// `split_tokens`. Markdown shards get stats injected and are sealed.
+/// Fails if a file whose contents are to be embedded cannot be read.
+pub fn render_shards(
+    options: &SnapshotOptions,
+    mut prepared: Vec<FileEntry>,
+    sniffed: Option<&Sniffed>,
+) -> Result<Vec<Shard>> {
+    if options.contents {
+        load_contents(&mut prepared)?;
+    }
 
-    //
-    // ============================================================
-    // 3. Render snapshot
-    // ============================================================
-    //
+    let fmt = select_formatter(options.format, options.show_lines);
+    let root = options.dir.as_str();
 
-    match &mut writer {
-        //
-        //  A) Markdown to file → buffer → inject → write at end
-        //
-        OutputTarget::Memory(buf) => {
+    let (is_markdown, colored) = match options.format {
+        Format::Markdown => (true, false),
+        Format::Text { colored } => (false, colored),
+    };
 
-            let split_k = args.split.as_ref()
-                .map(|opt| opt.unwrap_or(32))
-                .unwrap_or(0);
+    let packets = match options.split_tokens {
+        Some(target_tokens) => split_files_by_tokens(prepared, target_tokens),
+        None => vec![prepared],
+    };
 
-            if split_k > 0 {
-                let target_tokens = split_k * 1000;
-                let packets = split_files_by_tokens(prepared, target_tokens);
+    let total = packets.len();
 
-                let base = args.out.as_ref().unwrap().trim_end_matches(".md");
+    packets
+        .into_iter()
+        .enumerate()
+        .map(|(i, packet)| {
+            let mut buf = Vec::new();
 
-                
-                let total = packets.len();
+            if let Some(s) = sniffed {
+                write_sniff_header(&s.entry, &s.paths, is_markdown, colored, &mut buf);
+            }
+            fmt.print_preamble(root, &mut buf);
+            fmt.print_index(&packet, &mut buf);
 
-                for (i, packet) in packets.iter().enumerate() {
-                    let mut local_buf = Vec::new();
+            if options.contents {
+                fmt.print_contents(&packet, &mut buf)?;
+            }
 
-                    if let Some((ref entry, ref paths)) = sniff_meta {
-                        write_sniff_header(entry, paths, true, false, &mut local_buf);
-                    }
-                    fmt.print_preamble(&root, &mut local_buf);
-                    fmt.print_index(packet, &mut local_buf);
+            let shard_idx = options.split_tokens.map(|_| (i + 1, total));
 
-                    if args.contents {
-                        fmt.print_contents(packet, &mut local_buf);
-                    }
+            if is_markdown {
+                buf = finalize_markdown(&buf, shard_idx).into_bytes();
+            }
 
-                    let out_path = format!("{}.shard{:02}.md", base, i + 1);
+            let path = options.out.as_ref().map(|out| match shard_idx {
+                Some((n, _)) => format!("{}.shard{:02}.md", out.trim_end_matches(".md"), n),
+                None => out.clone(),
+            });
 
-                    finalize_markdown(&local_buf, &out_path, Some((i + 1, total)));
-                }
-            } else {
-                // original behavior
-                if let Some((ref entry, ref paths)) = sniff_meta {
-                    write_sniff_header(entry, paths, true, false, buf);
-                }
-                fmt.print_preamble(&root, buf);
-                fmt.print_index(&prepared, buf);
+            Ok(Shard { index: i + 1, total, files: packet, bytes: buf, path })
+        })
+        .collect()
+}
 
-                if args.contents {
-                    fmt.print_contents(&prepared, buf);
-                }
+/// Run the project snapshot (default command)
+pub fn run_snapshot(options: &SnapshotOptions) -> Result<()> {
 
-                let out_path = args.out.as_ref().unwrap();
-                finalize_markdown(buf.as_slice(), out_path, None);                
-            }
-        }
+    //
+    // ============================================================
+    // 0. HANDLE --sniff + 1. Run scan
+    // ============================================================
+    //
 
+    let (prepared, sniffed) = select_files(options)?;
+    announce_sniff(sniffed.as_ref());
 
-        //
-        //  B) stdout (no injection)
-        //
-        OutputTarget::Stdout => {
-            let out = &mut std::io::stdout();
+    //
+    // ============================================================
+    // 2. Render snapshot
+    // ============================================================
+    //
 
-            if let Some((ref entry, ref paths)) = sniff_meta {
-                let use_color = atty::is(atty::Stream::Stdout);
-                write_sniff_header(entry, paths, false, use_color, out);
-            }
-            fmt.print_preamble(&root, out);
-            fmt.print_index(&prepared, out);
+    let shards = render_shards(options, prepared, sniffed.as_ref())?;
 
-            if args.contents {
-                fmt.print_contents(&prepared, out);
-            }
-        }
+    //
+    // ============================================================
+    // 3. Write to --out (or shards) / stdout
+    // ============================================================
+    //
 
-        //
-        //  C) Should never occur
-        //
-        OutputTarget::File(_) => {
-            panic!("OutputTarget::File should not be used; use Memory for markdown.");
-        }
-    }
-}
\ No newline at end of file
+    write_shards(&shards)
+}
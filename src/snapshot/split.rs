@@ -1,19 +1,11 @@
 // src/snapshot/split.rs
 
 use crate::types::FileEntry;
-use std::fs;
-
-/// Estimate tokens for a file (cheap, deterministic)
-pub fn estimate_file_tokens(path: &str) -> usize {
-    let Ok(text) = fs::read_to_string(path) else {
-        return 0;
-    };
-
-    let words = text.split_whitespace().count();
-    ((words as f32) * 1.33).round() as usize
-}
 
 /// Partition files into token-bounded packets
+///
+/// Uses the token estimate already recorded on each entry, so planning never
+/// re-reads files (and works for entries restored from the snapshot store).
 pub fn split_files_by_tokens(
     files: Vec<FileEntry>,
     target_tokens: usize,
@@ -24,7 +16,7 @@ pub fn split_files_by_tokens(
     let mut current_tokens = 0usize;
 
     for file in files {
-        let est = estimate_file_tokens(&file.path);
+        let est = file.token_est;
 
         // If adding this file would exceed the packet budget
         if !current.is_empty() && current_tokens + est > target_tokens {
@@ -0,0 +1,152 @@
+use crate::codex::integrity::content_hash;
+use crate::types::FileEntry;
+
+/// A recorded snapshot: which files were selected and what they contained.
+///
+/// Serialized as a small text file:
+///
+/// ```text
+/// # YGG SNAPSHOT
+/// timestamp_unix: 1760000000
+/// root: .
+/// message: before the split
+///
+/// <hash>\t<lines>\t<words>\t<tokens>\t<path>
+/// ```
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    pub timestamp: u64,
+    pub root: String,
+    pub message: Option<String>,
+    pub files: Vec<FileEntry>,
+}
+
+impl Manifest {
+    /// Tab-separated file table (the part the snapshot id is derived from).
+    fn file_table(&self) -> String {
+        self.files
+            .iter()
+            .map(|f| {
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\n",
+                    f.hash, f.line_count, f.word_count, f.token_est, f.path
+                )
+            })
+            .collect()
+    }
+
+    /// Content-addressed id: BLAKE3 over the root and file table, so the same
+    /// selection of the same contents always yields the same snapshot.
+    pub fn id(&self) -> String {
+        content_hash(&format!("root: {}\n{}", self.root, self.file_table()))
+    }
+
+    pub fn total_lines(&self) -> usize {
+        self.files.iter().map(|f| f.line_count).sum()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# YGG SNAPSHOT\n");
+        text.push_str(&format!("timestamp_unix: {}\n", self.timestamp));
+        text.push_str(&format!("root: {}\n", self.root));
+        if let Some(msg) = &self.message {
+            text.push_str(&format!("message: {}\n", msg));
+        }
+        text.push('\n');
+        text.push_str(&self.file_table());
+        text
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        if lines.next()? != "# YGG SNAPSHOT" {
+            return None;
+        }
+
+        let mut manifest = Manifest {
+            timestamp: 0,
+            root: ".".to_string(),
+            message: None,
+            files: Vec::new(),
+        };
+
+        for line in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            if let Some(v) = line.strip_prefix("timestamp_unix: ") {
+                manifest.timestamp = v.parse().unwrap_or(0);
+            } else if let Some(v) = line.strip_prefix("root: ") {
+                manifest.root = v.to_string();
+            } else if let Some(v) = line.strip_prefix("message: ") {
+                manifest.message = Some(v.to_string());
+            }
+        }
+
+        for line in lines {
+            let cols: Vec<&str> = line.splitn(5, '\t').collect();
+            if cols.len() != 5 {
+                continue;
+            }
+            manifest.files.push(FileEntry {
+                path: cols[4].to_string(),
+                line_count: cols[1].parse().unwrap_or(0),
+                word_count: cols[2].parse().unwrap_or(0),
+                token_est: cols[3].parse().unwrap_or(0),
+                hash: cols[0].to_string(),
+                blob: None,
+                text: None,
+            });
+        }
+
+        Some(manifest)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Manifest {
+        Manifest {
+            timestamp: 1_760_000_000,
+            root: ".".into(),
+            message: Some("before the split".into()),
+            files: vec![FileEntry {
+                path: "./src/my file.rs".into(),
+                line_count: 3,
+                word_count: 7,
+                token_est: 9,
+                hash: "abc123".into(),
+                blob: None,
+                text: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let m = sample();
+        let parsed = Manifest::parse(&m.to_text()).unwrap();
+
+        assert_eq!(parsed.timestamp, m.timestamp);
+        assert_eq!(parsed.message.as_deref(), Some("before the split"));
+        assert_eq!(parsed.files.len(), 1);
+        assert_eq!(parsed.files[0].path, "./src/my file.rs");
+        assert_eq!(parsed.files[0].token_est, 9);
+        assert_eq!(parsed.id(), m.id());
+    }
+
+    #[test]
+    fn test_id_ignores_timestamp_and_message() {
+        let a = sample();
+        let mut b = sample();
+        b.timestamp += 60;
+        b.message = None;
+        assert_eq!(a.id(), b.id());
+
+        b.files[0].hash = "def456".into();
+        assert_ne!(a.id(), b.id());
+    }
+}
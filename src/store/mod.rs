@@ -0,0 +1,5 @@
+pub mod manifest;
+pub mod objects;
+pub mod run;
+
+pub use run::{print_log, run_log, run_show, run_snap};
@@ -0,0 +1,129 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::manifest::Manifest;
+
+/// Name of the store directory, created under `--dir`.
+pub const STORE_DIR: &str = ".ygg";
+
+/// Content-addressed local store:
+///
+/// ```text
+/// .ygg/objects/<2 hex>/<62 hex>   file contents, keyed by BLAKE3
+/// .ygg/snapshots/<id>             snapshot manifests, keyed by BLAKE3
+/// ```
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    pub fn open(dir: &str) -> Self {
+        Store { root: Path::new(dir).join(STORE_DIR) }
+    }
+
+    pub fn object_path(&self, hash: &str) -> PathBuf {
+        let (prefix, rest) = hash.split_at(2.min(hash.len()));
+        self.root.join("objects").join(prefix).join(rest)
+    }
+
+    /// Store file contents under their hash (no-op if already present).
+    pub fn put_object(&self, hash: &str, contents: &str) -> io::Result<()> {
+        let path = self.object_path(hash);
+        if path.exists() {
+            return Ok(());
+        }
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, contents)
+    }
+
+    fn snapshots_dir(&self) -> PathBuf {
+        self.root.join("snapshots")
+    }
+
+    /// Record a manifest. Returns its id and whether it was new.
+    pub fn put_manifest(&self, manifest: &Manifest) -> io::Result<(String, bool)> {
+        let id = manifest.id();
+        let path = self.snapshots_dir().join(&id);
+        if path.exists() {
+            return Ok((id, false));
+        }
+        fs::create_dir_all(self.snapshots_dir())?;
+        fs::write(path, manifest.to_text())?;
+        Ok((id, true))
+    }
+
+    /// All recorded snapshots, newest first.
+    pub fn list(&self) -> Vec<(String, Manifest)> {
+        let Ok(entries) = fs::read_dir(self.snapshots_dir()) else {
+            return Vec::new();
+        };
+
+        let mut snaps: Vec<(String, Manifest)> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let id = e.file_name().to_string_lossy().to_string();
+                let text = fs::read_to_string(e.path()).ok()?;
+                Manifest::parse(&text).map(|m| (id, m))
+            })
+            .collect();
+
+        snaps.sort_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp).then(a.0.cmp(&b.0)));
+        snaps
+    }
+
+    /// Look a snapshot up by full id or unique prefix.
+    pub fn find(&self, prefix: &str) -> Result<(String, Manifest), String> {
+        let matches: Vec<_> = self
+            .list()
+            .into_iter()
+            .filter(|(id, _)| id.starts_with(prefix))
+            .collect();
+
+        match matches.len() {
+            0 => Err(format!("no snapshot matches '{}'", prefix)),
+            1 => Ok(matches.into_iter().next().unwrap()),
+            n => Err(format!("'{}' is ambiguous ({} snapshots match)", prefix, n)),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileEntry;
+
+    #[test]
+    fn test_store_roundtrip_and_prefix_lookup() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = Store::open(tmp.path().to_str().unwrap());
+
+        store.put_object("abcdef", "hello\n").unwrap();
+        assert_eq!(fs::read_to_string(store.object_path("abcdef")).unwrap(), "hello\n");
+
+        let manifest = Manifest {
+            timestamp: 1,
+            root: ".".into(),
+            message: None,
+            files: vec![FileEntry {
+                path: "a.txt".into(),
+                line_count: 1,
+                word_count: 1,
+                token_est: 1,
+                hash: "abcdef".into(),
+                blob: None,
+                text: None,
+            }],
+        };
+
+        let (id, fresh) = store.put_manifest(&manifest).unwrap();
+        assert!(fresh);
+        assert!(!store.put_manifest(&manifest).unwrap().1);
+
+        let (found, m) = store.find(&id[..8]).unwrap();
+        assert_eq!(found, id);
+        assert_eq!(m.files[0].path, "a.txt");
+        assert!(store.find("zzzz").is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use colored::*;
+
+use crate::codex::integrity::{content_hash, short_hash};
+use crate::error::{Error, Result};
+use crate::snapshot::options::SnapshotOptions;
+use crate::snapshot::run::{render_shards, select_files, Sniffed};
+use crate::snapshot::writer::write_shards;
+
+use super::manifest::Manifest;
+use super::objects::Store;
+
+/// Format a unix timestamp as `YYYY-MM-DD HH:MM:SS UTC`.
+fn format_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // civil-from-days (Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year, month, day, rem / 3600, (rem % 3600) / 60, rem % 60
+    )
+}
+
+/// A snapshot written by `ygg snap`.
+#[derive(Debug, Clone)]
+pub struct Recorded {
+    pub id: String,
+    /// False when an identical snapshot was already in the store
+    pub fresh: bool,
+    pub manifest: Manifest,
+    /// What `--sniff` discovered, if it was used
+    pub sniffed: Option<Sniffed>,
+}
+
+impl Recorded {
+    /// The confirmation line `ygg snap` prints.
+    pub fn print(&self, out: &mut dyn Write) {
+        let id = short_hash(&self.id).truecolor(255, 200, 50).bold();
+        if self.fresh {
+            writeln!(
+                out,
+                "🌳 Snapshot {} recorded ({} files, {} LOC)",
+                id,
+                self.manifest.files.len(),
+                self.manifest.total_lines()
+            ).unwrap();
+        } else {
+            writeln!(out, "🌳 Snapshot {} already recorded — nothing changed", id).unwrap();
+        }
+    }
+}
+
+/// `ygg snap` — record the current selection into the `.ygg` store.
+pub fn run_snap(options: &SnapshotOptions, message: Option<String>) -> Result<Recorded> {
+    let (files, sniffed) = select_files(options)?;
+    if files.is_empty() {
+        return Err(Error::InvalidOption(
+            "nothing selected — pass --only, --show, --white or --sniff".to_string(),
+        ));
+    }
+
+    let store = Store::open(&options.dir);
+    let mut recorded = Vec::with_capacity(files.len());
+
+    for mut entry in files {
+        // a snapshot missing a selected file would replay as if it never existed
+        let contents = entry.read_contents().map_err(|e| Error::io(&entry.path, e))?;
+
+        // the file may have changed since it was scanned
+        entry.hash = content_hash(&contents);
+
+        store
+            .put_object(&entry.hash, &contents)
+            .map_err(|e| Error::io(&entry.path, e))?;
+        recorded.push(entry);
+    }
+
+    let manifest = Manifest {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        root: options.dir.clone(),
+        message,
+        files: recorded,
+    };
+
+    let (id, fresh) = store
+        .put_manifest(&manifest)
+        .map_err(|e| Error::Store(format!("failed to write manifest: {}", e)))?;
+
+    Ok(Recorded { id, fresh, manifest, sniffed })
+}
+
+/// `ygg log` — recorded snapshots, newest first.
+pub fn run_log(dir: &str) -> Vec<(String, Manifest)> {
+    Store::open(dir).list()
+}
+
+/// The history listing `ygg log` prints.
+pub fn print_log(snaps: &[(String, Manifest)], out: &mut dyn Write) {
+    if snaps.is_empty() {
+        writeln!(out, "No snapshots recorded yet. Run `ygg snap --only <paths>` first.").unwrap();
+        return;
+    }
+
+    writeln!(out, "{}", "📜 Snapshot history".bright_magenta().bold()).unwrap();
+
+    for (id, m) in snaps {
+        let line = format!(
+            "{}  {}  {:>4} files  {:>7} LOC  {}",
+            short_hash(id).truecolor(255, 200, 50),
+            format_utc(m.timestamp),
+            m.files.len(),
+            m.total_lines(),
+            m.message.as_deref().unwrap_or_default()
+        );
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+}
+
+/// `ygg show <id>` — re-render a recorded snapshot with the usual output flags.
+pub fn run_show(id: &str, options: &SnapshotOptions) -> Result<()> {
+    let store = Store::open(&options.dir);
+    let (_, manifest) = store.find(id).map_err(Error::Store)?;
+
+    let entries = manifest
+        .files
+        .iter()
+        .cloned()
+        .map(|mut f| {
+            f.blob = Some(store.object_path(&f.hash).to_string_lossy().to_string());
+            f
+        })
+        .collect();
+
+    // the preamble shows the root the snapshot was taken from
+    let options = SnapshotOptions { dir: manifest.root.clone(), ..options.clone() };
+
+    write_shards(&render_shards(&options, entries, None)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_utc(1_760_745_600), "2025-10-18 00:00:00 UTC");
+        assert_eq!(format_utc(951_825_599), "2000-02-29 11:59:59 UTC");
+    }
+}
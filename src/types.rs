@@ -1,10 +1,15 @@
 // src/types.rs
+use crate::diff::options::HunkOptions;
+
 #[derive(Debug, Clone)]
 pub struct BlockMatch {
     pub from_file: String,
     pub from_range: (usize, usize),
     pub to_file: String,
     pub to_range: (usize, usize),
+    /// Character-level similarity of the two blocks (100 unless a fuzzy
+    /// `MatchMode` matched edited lines)
+    pub similarity: u8,
 }
 
 #[derive(Debug, Clone)]
@@ -18,6 +23,8 @@ pub struct GroupedMatches {
     pub from_file: String,
     pub to_file: String,
     pub blocks: Vec<BlockWithVote>,
+    /// Contents of `from_file` as diffed (it may not exist on disk, e.g. a codex)
+    pub from_content: String,
 }
 
 #[derive(Debug, Clone)]
@@ -26,4 +33,103 @@ pub struct FileEntry {
     pub line_count: usize,
     pub word_count: usize,
     pub token_est: usize,
-}
\ No newline at end of file
+    /// BLAKE3 hash of the file contents (hex).
+    pub hash: String,
+    /// Where to read contents from when not the live file at `path`
+    /// (e.g. an object in the `.ygg` snapshot store).
+    pub blob: Option<String>,
+    /// Contents already read for rendering; `hash` was computed from them.
+    pub text: Option<String>,
+}
+
+impl FileEntry {
+    /// Read the file contents: the text already loaded, else the stored
+    /// blob, else the live file.
+    pub fn read_contents(&self) -> std::io::Result<String> {
+        match &self.text {
+            Some(text) => Ok(text.clone()),
+            None => std::fs::read_to_string(self.blob.as_deref().unwrap_or(&self.path)),
+        }
+    }
+}
+/// One file on one side of a diff.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    /// Path relative to the root it was found under (the pairing key)
+    pub rel: String,
+    /// Path as given / found on disk, for display
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Added,
+    Removed,
+    Modified,
+    /// Paired across different relative paths (contents may also differ)
+    Renamed,
+    /// Added file derived from a file that still exists on the old side
+    Copied,
+    Unchanged,
+}
+
+impl FileStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileStatus::Added => "added",
+            FileStatus::Removed => "removed",
+            FileStatus::Modified => "modified",
+            FileStatus::Renamed => "renamed",
+            FileStatus::Copied => "copied",
+            FileStatus::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// A file paired across the two sides of a diff.
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub status: FileStatus,
+    pub from: Option<SourceFile>,
+    pub to: Option<SourceFile>,
+    /// Content similarity (0–100) for renames and copies
+    pub similarity: Option<u8>,
+}
+
+impl FileDiff {
+    /// Relative path of whichever side exists (the new side when both do).
+    pub fn rel(&self) -> &str {
+        self.to
+            .as_ref()
+            .or(self.from.as_ref())
+            .map(|f| f.rel.as_str())
+            .unwrap_or("")
+    }
+}
+
+/// One line of a hunk: `op` is `' '`, `'-'` or `'+'`; `text` has no newline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HunkLine {
+    pub op: char,
+    pub text: String,
+}
+
+/// A contiguous change with its context (1-based line numbers, as in `@@`).
+#[derive(Debug, Clone)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<HunkLine>,
+}
+
+/// Everything one `ygg diff` run found: paired files and cross-file moves.
+#[derive(Debug, Clone, Default)]
+pub struct DiffReport {
+    pub files: Vec<FileDiff>,
+    pub moves: Vec<GroupedMatches>,
+    /// How formatters cut modified files into hunks
+    pub hunks: HunkOptions,
+}
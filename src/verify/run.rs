@@ -0,0 +1,118 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use colored::*;
+
+use crate::codex::integrity::{check_seal, content_hash, SealStatus};
+use crate::codex::parse_codex;
+use crate::error::{Error, Result};
+
+/// Per-file verdict when checking a codex against the working tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileCheck {
+    Unchanged,
+    Drifted,
+    Missing,
+    Unhashed,
+}
+
+/// Check every `<file>` block of a codex against the files under `dir`.
+pub fn verify_codex(text: &str, dir: &str) -> Vec<(String, FileCheck)> {
+    parse_codex(text)
+        .files
+        .into_iter()
+        .map(|f| {
+            let check = match &f.hash {
+                None => FileCheck::Unhashed,
+                Some(expected) => match fs::read_to_string(Path::new(dir).join(&f.path)) {
+                    Err(_) => FileCheck::Missing,
+                    Ok(current) if content_hash(&current) == *expected => FileCheck::Unchanged,
+                    Ok(_) => FileCheck::Drifted,
+                },
+            };
+            (f.path, check)
+        })
+        .collect()
+}
+
+/// What `ygg verify` found: the codex seal and a verdict per `<file>` block.
+#[derive(Debug, Clone)]
+pub struct Verification {
+    pub codex: String,
+    pub seal: SealStatus,
+    pub files: Vec<(String, FileCheck)>,
+}
+
+impl Verification {
+    /// True when the codex is intact and every file still matches the
+    /// working tree (a codex without `<file>` blocks never is).
+    pub fn is_clean(&self) -> bool {
+        self.seal == SealStatus::Intact
+            && !self.files.is_empty()
+            && self.files.iter().all(|(_, check)| *check == FileCheck::Unchanged)
+    }
+
+    /// The report `ygg verify` prints.
+    pub fn print(&self, out: &mut dyn Write) {
+        writeln!(out, "{} {}", "🔍 Verifying codex:".bright_magenta().bold(), self.codex).unwrap();
+
+        match &self.seal {
+            SealStatus::Intact => writeln!(out, "🔒 Codex seal: {}", "intact".green()).unwrap(),
+            SealStatus::Tampered { expected, actual } => writeln!(
+                out,
+                "🔓 Codex seal: {} (recorded {}, computed {})",
+                "TAMPERED".red().bold(),
+                expected,
+                actual
+            ).unwrap(),
+            SealStatus::Missing => writeln!(out, "⚠️  Codex seal: {}", "not sealed".yellow()).unwrap(),
+        }
+        writeln!(out).unwrap();
+
+        if self.files.is_empty() {
+            return;
+        }
+
+        let (mut unchanged, mut drifted, mut missing, mut unhashed) = (0, 0, 0, 0);
+
+        for (path, check) in &self.files {
+            match check {
+                FileCheck::Unchanged => {
+                    unchanged += 1;
+                    writeln!(out, "  {} {}", "✔".green(), path).unwrap();
+                }
+                FileCheck::Drifted => {
+                    drifted += 1;
+                    writeln!(out, "  {} {} {}", "✎".yellow(), path, "(drifted)".yellow()).unwrap();
+                }
+                FileCheck::Missing => {
+                    missing += 1;
+                    writeln!(out, "  {} {} {}", "✖".red(), path, "(missing)".red()).unwrap();
+                }
+                FileCheck::Unhashed => {
+                    unhashed += 1;
+                    writeln!(out, "  {} {} {}", "?".dimmed(), path, "(no hash recorded)".dimmed()).unwrap();
+                }
+            }
+        }
+
+        writeln!(
+            out,
+            "\n📦 {} unchanged, {} drifted, {} missing, {} unhashed",
+            unchanged, drifted, missing, unhashed
+        ).unwrap();
+    }
+}
+
+/// `ygg verify <codex>` — check the seal of `codex` and each of its files
+/// against the working tree under `dir`.
+pub fn run_verify(codex: &str, dir: &str) -> Result<Verification> {
+    let text = fs::read_to_string(codex).map_err(|e| Error::io(codex, e))?;
+
+    Ok(Verification {
+        codex: codex.to_string(),
+        seal: check_seal(&text),
+        files: verify_codex(&text, dir),
+    })
+}
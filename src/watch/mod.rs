@@ -0,0 +1,3 @@
+pub mod run;
+
+pub use run::run_watch;
@@ -0,0 +1,414 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+use colored::*;
+use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode};
+use walkdir::WalkDir;
+
+use crate::error::{Error, Result};
+use crate::scanner::cache::{stat_key, StatsCache};
+use crate::scanner::collect::file_entry;
+use crate::scanner::selection::Selection;
+use crate::snapshot::filelist::prepare_file_list;
+use crate::snapshot::options::SnapshotOptions;
+use crate::snapshot::run::{announce_sniff, load_contents, render_shards, select_files, Sniffed};
+use crate::snapshot::writer::write_shards;
+use crate::store::objects::STORE_DIR;
+use crate::types::FileEntry;
+
+/// Quiet period before a burst of file events triggers a rebuild.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// mtime/size of a path, `None` when it does not exist.
+type Stamp = Option<(u128, u64)>;
+
+/// What the watcher needs to decide whether an event matters, and the last
+/// rendered selection, patched on each change instead of re-scanning.
+pub struct WatchContext {
+    /// Canonical `--dir`
+    pub root: PathBuf,
+    /// `--dir` as the user spelled it (collect_files paths start with it)
+    pub dir: String,
+    /// `--out` (canonical parent); its `.shardNN.md` siblings are ours too
+    pub out: Option<PathBuf>,
+    /// Files selected by the last regeneration (canonical) and their stamps
+    pub selected: HashMap<PathBuf, Stamp>,
+    /// Entries of the selected files; contents stay loaded between renders
+    pub files: HashMap<PathBuf, FileEntry>,
+    /// Directories under the root and their stamps, to notice entries moved
+    /// in or out without a per-file event
+    pub dirs: HashMap<PathBuf, Stamp>,
+    /// Filters, to catch newly created files that would be selected
+    pub selection: Selection,
+    /// What `--sniff` traced, for the header
+    pub sniffed: Option<Sniffed>,
+}
+
+impl WatchContext {
+    /// Is `path` the codex we write, or one of its shards?
+    fn is_output(&self, path: &Path) -> bool {
+        let Some(out) = &self.out else { return false };
+        if path == out {
+            return true;
+        }
+        let (Some(name), Some(out_name)) = (path.file_name(), out.file_name()) else {
+            return false;
+        };
+        let stem = out_name.to_string_lossy();
+        let stem = stem.trim_end_matches(".md");
+        let shard = name
+            .to_string_lossy()
+            .strip_prefix(stem)
+            .and_then(|rest| rest.strip_prefix(".shard"))
+            .and_then(|rest| rest.strip_suffix(".md"))
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()));
+        shard && path.parent() == out.parent()
+    }
+
+    /// `path` spelled the way `collect_files` spells it, if under the root.
+    fn display(&self, path: &Path) -> Option<String> {
+        let rel = path.strip_prefix(&self.root).ok()?;
+        Some(format!("{}/{}", self.dir, rel.to_string_lossy()))
+    }
+
+    /// Does a change at `path` require regenerating the codex?
+    pub fn is_relevant(&self, path: &Path) -> bool {
+        // our own store and cache
+        if path.components().any(|c| c.as_os_str() == STORE_DIR) {
+            return false;
+        }
+
+        // our own output (SHOW.md, SHOW.shard01.md, …)
+        if self.is_output(path) {
+            return false;
+        }
+
+        if self.selected.contains_key(path) {
+            return true;
+        }
+
+        // a new or previously unselected file that now passes the filters
+        match self.display(path) {
+            Some(display) => self.selection.accepts(&display),
+            None => false,
+        }
+    }
+
+    /// Relevant *and* actually different from what was last rendered.
+    ///
+    /// Reading files to build the codex raises access events on some
+    /// platforms; comparing stamps keeps those from re-triggering a rebuild.
+    pub fn is_changed(&self, path: &Path, now: Stamp) -> bool {
+        // a known directory gained, lost or renamed entries, or is gone
+        if let Some(before) = self.dirs.get(path) {
+            return *before != now;
+        }
+        if !self.is_relevant(path) {
+            return false;
+        }
+        match self.selected.get(path) {
+            Some(before) => *before != now,
+            None => now.is_some(),
+        }
+    }
+
+    /// Record the stamps of `dir` and every directory below it.
+    fn stamp_dirs(&mut self, dir: &Path) {
+        let walker = WalkDir::new(dir)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != STORE_DIR);
+        for entry in walker.filter_map(|e| e.ok()).filter(|e| e.file_type().is_dir()) {
+            let stamp = stat_key(&entry.path().to_string_lossy());
+            self.dirs.insert(entry.into_path(), stamp);
+        }
+    }
+
+    /// Re-stat one file; true when its entry was added or replaced.
+    fn refresh(&mut self, path: &Path, cache: &mut StatsCache) -> bool {
+        let Some(display) = self.display(path) else { return false };
+        if self.is_output(path) || !self.selection.accepts(&display) {
+            return false;
+        }
+        let stamp = stat_key(&display);
+        if self.selected.get(path) == Some(&stamp) {
+            return false;
+        }
+        self.selected.insert(path.to_path_buf(), stamp);
+        self.files.insert(path.to_path_buf(), file_entry(display, cache));
+        true
+    }
+
+    /// Drop `path` and everything below it; returns how many files went.
+    fn forget(&mut self, path: &Path) -> usize {
+        self.dirs.retain(|d, _| !d.starts_with(path));
+        self.files.retain(|f, _| !f.starts_with(path));
+        let before = self.selected.len();
+        self.selected.retain(|f, _| !f.starts_with(path));
+        before - self.selected.len()
+    }
+
+    /// Patch the selection for the `changed` paths only: re-stat changed
+    /// files, walk changed directories, drop what vanished. Every other file
+    /// keeps its entry and loaded contents. Returns how many files changed.
+    pub fn update(&mut self, changed: &[PathBuf], cache: &mut StatsCache) -> usize {
+        let mut n = 0;
+
+        for path in changed {
+            let path = path.canonicalize().unwrap_or_else(|_| path.clone());
+
+            if path.is_dir() {
+                self.stamp_dirs(&path);
+                let walker = WalkDir::new(&path)
+                    .into_iter()
+                    .filter_entry(|e| e.file_name() != STORE_DIR);
+                for entry in walker.filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+                    n += self.refresh(entry.path(), cache) as usize;
+                }
+            } else if path.is_file() {
+                n += self.refresh(&path, cache) as usize;
+            } else {
+                n += self.forget(&path);
+            }
+        }
+
+        // the cache only saves work; failing to persist it must not stop watching
+        let _ = cache.save();
+        n
+    }
+
+    /// Render the current selection, reading only contents not yet loaded.
+    fn render(&mut self, options: &SnapshotOptions) -> Result<()> {
+        if options.contents {
+            load_contents(self.files.values_mut())?;
+        }
+
+        let files = prepare_file_list(self.files.values().cloned().collect());
+        write_shards(&render_shards(options, files, self.sniffed.as_ref())?)
+    }
+}
+
+/// Run the snapshot pipeline once and capture what to watch next.
+fn regenerate(options: &SnapshotOptions, root: &Path) -> Result<WatchContext> {
+    let (files, sniffed) = select_files(options)?;
+    announce_sniff(sniffed.as_ref());
+
+    let out = options.out.as_ref().map(|out| {
+        let p = Path::new(out);
+        let parent = p
+            .parent()
+            .filter(|d| !d.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        parent
+            .canonicalize()
+            .unwrap_or_else(|_| parent.to_path_buf())
+            .join(p.file_name().unwrap_or_default())
+    });
+
+    let files: HashMap<PathBuf, FileEntry> = files
+        .into_iter()
+        .filter_map(|f| Some((Path::new(&f.path).canonicalize().ok()?, f)))
+        .collect();
+    let selected = files
+        .iter()
+        .map(|(canon, f)| (canon.clone(), stat_key(&f.path)))
+        .collect();
+
+    // sniff may have widened `only`; new files are judged against that
+    let mut widened = options.clone();
+    if let Some(s) = &sniffed {
+        widened.only.extend(s.paths.iter().cloned());
+    }
+
+    let mut ctx = WatchContext {
+        root: root.to_path_buf(),
+        dir: options.dir.trim_end_matches('/').to_string(),
+        out,
+        selected,
+        files,
+        dirs: HashMap::new(),
+        selection: Selection::from_options(&widened),
+        sniffed,
+    };
+    ctx.stamp_dirs(root);
+
+    ctx.render(options)?;
+    Ok(ctx)
+}
+
+/// `ygg --watch` — regenerate the snapshot whenever selected files change.
+///
+/// Only the changed paths are re-read. With `--sniff` an edit may add or
+/// drop an import, so every change re-traces and re-scans instead.
+pub fn run_watch(options: &SnapshotOptions) -> Result<()> {
+    let root = Path::new(&options.dir)
+        .canonicalize()
+        .map_err(|_| Error::RootNotFound(options.dir.clone()))?;
+
+    let (tx, rx) = channel();
+    let mut debouncer = new_debouncer(DEBOUNCE, tx)
+        .map_err(|e| Error::Watch(format!("could not start file watcher: {}", e)))?;
+
+    debouncer
+        .watcher()
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| Error::Watch(format!("could not watch '{}': {}", options.dir, e)))?;
+
+    let mut ctx = regenerate(options, &root)?;
+    let mut cache = if options.cache {
+        StatsCache::load(&options.dir)
+    } else {
+        StatsCache::disabled()
+    };
+
+    eprintln!(
+        "{} {} ({} files) — Ctrl+C to stop",
+        "🌲 Watching".bright_magenta().bold(),
+        options.dir.truecolor(0, 255, 255),
+        ctx.selected.len()
+    );
+
+    for result in rx {
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("⚠️  Watch error: {}", e);
+                continue;
+            }
+        };
+
+        let changed: HashSet<PathBuf> = events
+            .into_iter()
+            .map(|e| e.path)
+            .filter(|p| ctx.is_changed(p, stat_key(&p.to_string_lossy())))
+            .collect();
+
+        if changed.is_empty() {
+            continue;
+        }
+
+        let rendered = if options.sniff.is_some() {
+            regenerate(options, &root).map(|next| {
+                ctx = next;
+                changed.len()
+            })
+        } else {
+            let changed: Vec<PathBuf> = changed.into_iter().collect();
+            match ctx.update(&changed, &mut cache) {
+                0 => continue,
+                n => ctx.render(options).map(|_| n),
+            }
+        };
+
+        match rendered {
+            Ok(n) => eprintln!(
+                "🔁 Regenerated after {} change{} ({} files selected)",
+                n,
+                if n == 1 { "" } else { "s" },
+                ctx.selected.len()
+            ),
+            // keep watching; the next save may fix it
+            Err(e) => eprintln!("⚠️  {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> WatchContext {
+        WatchContext {
+            root: PathBuf::from("/proj"),
+            dir: ".".into(),
+            out: Some(PathBuf::from("/proj/SHOW.md")),
+            selected: [(PathBuf::from("/proj/src/a.py"), Some((10, 3)))]
+                .into_iter()
+                .collect(),
+            files: HashMap::new(),
+            dirs: HashMap::new(),
+            selection: Selection {
+                show: vec!["py".into()],
+                ignore: vec!["__pycache__".into()],
+                only: vec![],
+            },
+            sniffed: None,
+        }
+    }
+
+    #[test]
+    fn test_selected_and_new_matching_files_are_relevant() {
+        let c = ctx();
+        assert!(c.is_relevant(Path::new("/proj/src/a.py")));
+        assert!(c.is_relevant(Path::new("/proj/src/new.py")));
+        assert!(c.is_relevant(Path::new("/proj/SHOWCASE.py")));
+        assert!(c.is_relevant(Path::new("/proj/SHOW.shardXY.py")));
+    }
+
+    #[test]
+    fn test_outputs_store_and_filtered_files_are_ignored() {
+        let c = ctx();
+        assert!(!c.is_relevant(Path::new("/proj/SHOW.md")));
+        assert!(!c.is_relevant(Path::new("/proj/SHOW.shard02.md")));
+        assert!(!c.is_relevant(Path::new("/proj/.ygg/objects/ab")));
+        assert!(!c.is_relevant(Path::new("/proj/src/notes.txt")));
+        assert!(!c.is_relevant(Path::new("/proj/__pycache__/a.py")));
+        assert!(!c.is_relevant(Path::new("/elsewhere/b.py")));
+    }
+
+    #[test]
+    fn test_reads_without_modification_do_not_retrigger() {
+        let c = ctx();
+        let a = Path::new("/proj/src/a.py");
+        assert!(!c.is_changed(a, Some((10, 3))));
+        assert!(c.is_changed(a, Some((11, 3))));
+        assert!(c.is_changed(a, None));
+        assert!(c.is_changed(Path::new("/proj/src/new.py"), Some((1, 1))));
+        assert!(!c.is_changed(Path::new("/proj/src/gone.py"), None));
+    }
+
+    #[test]
+    fn test_update_rereads_only_changed_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let (a, b) = (root.join("src/a.py"), root.join("src/b.py"));
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(&a, "x = 1\n").unwrap();
+        std::fs::write(&b, "y = 2\n").unwrap();
+        std::fs::write(root.join("src/notes.txt"), "skip\n").unwrap();
+
+        let mut c = WatchContext {
+            root: root.clone(),
+            dir: root.to_string_lossy().into(),
+            out: None,
+            selected: HashMap::new(),
+            files: HashMap::new(),
+            dirs: HashMap::new(),
+            selection: Selection { show: vec!["py".into()], ignore: vec![], only: vec![] },
+            sniffed: None,
+        };
+        let mut cache = StatsCache::disabled();
+
+        // a directory that appeared is walked
+        assert_eq!(c.update(&[root.join("src")], &mut cache), 2);
+        assert!(c.dirs.contains_key(&root.join("src")));
+
+        // loaded contents of untouched files are kept
+        c.files.get_mut(&b).unwrap().text = Some("y = 2\n".into());
+        std::fs::write(&a, "x = 1\nz = 3\n").unwrap();
+        assert_eq!(c.update(std::slice::from_ref(&a), &mut cache), 1);
+        assert_eq!(c.files[&a].line_count, 2);
+        assert!(c.files[&b].text.is_some());
+        assert_eq!(c.update(&[root.join("src")], &mut cache), 0);
+
+        // a vanished directory takes its files with it
+        std::fs::remove_dir_all(root.join("src")).unwrap();
+        assert!(c.is_changed(&root.join("src"), None));
+        assert_eq!(c.update(&[root.join("src")], &mut cache), 2);
+        assert!(c.files.is_empty() && c.selected.is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+use std::fs;
+
+use yggdrasil_cli::{Error, Format, Snapshot};
+
+fn sample_tree() -> tempfile::TempDir {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir(root.join("src")).unwrap();
+    fs::write(root.join("src/main.py"), "import helper\n\nhelper.run()\n").unwrap();
+    fs::write(root.join("src/helper.py"), "def run():\n    pass\n").unwrap();
+    fs::write(root.join("notes.txt"), "not code\n").unwrap();
+    tmpdir
+}
+
+#[test]
+fn test_builder_selects_files() {
+    let tree = sample_tree();
+    let root = tree.path().to_str().unwrap();
+
+    let files = Snapshot::builder()
+        .root(root)
+        .show(["py"])
+        .cache(false)
+        .build()
+        .unwrap()
+        .files()
+        .unwrap();
+
+    let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths.len(), 2);
+    assert!(paths[0].ends_with("src/helper.py"));
+    assert!(paths[1].ends_with("src/main.py"));
+    assert_eq!(files[1].line_count, 3);
+}
+
+#[test]
+fn test_builder_renders_markdown_without_touching_disk() {
+    let tree = sample_tree();
+    let root = tree.path().to_str().unwrap();
+
+    let rendered = Snapshot::builder()
+        .root(root)
+        .sniff(format!("{}/src/main.py", root))
+        .format(Format::Markdown)
+        .contents(true)
+        .build()
+        .unwrap()
+        .render()
+        .unwrap();
+
+    assert!(!tree.path().join(".ygg").exists());
+    assert_eq!(rendered.sniffed.as_ref().unwrap().paths.len(), 2);
+    assert_eq!(rendered.shards.len(), 1);
+    assert!(rendered.shards[0].path.is_none());
+
+    let text = String::from_utf8(rendered.bytes()).unwrap();
+    assert!(text.contains("# CODEX"));
+    assert!(text.contains("codex_hash: "));
+    assert!(text.contains("def run():"));
+    assert!(!text.contains("not code"));
+}
+
+#[test]
+fn test_builder_splits_into_shards() {
+    let tree = sample_tree();
+    let root = tree.path().to_str().unwrap();
+
+    let rendered = Snapshot::builder()
+        .root(root)
+        .show(["py", "txt"])
+        .format(Format::Markdown)
+        .contents(true)
+        .split(1)
+        .out("CODEX.md")
+        .cache(false)
+        .build()
+        .unwrap()
+        .render()
+        .unwrap();
+
+    assert_eq!(rendered.shards.len(), 3);
+    assert_eq!(rendered.shards[1].path.as_deref(), Some("CODEX.shard02.md"));
+    assert!(String::from_utf8_lossy(&rendered.shards[2].bytes).contains("SHARD 3 / 3"));
+}
+
+#[test]
+fn test_builder_reports_errors() {
+    let missing = Snapshot::builder().root("/definitely/not/here").build();
+    assert!(matches!(missing, Err(Error::RootNotFound(_))));
+
+    let tree = sample_tree();
+    let outside = Snapshot::builder()
+        .root(tree.path().join("src").to_str().unwrap())
+        .sniff("/elsewhere/entry.py")
+        .build()
+        .unwrap()
+        .render();
+    assert!(matches!(outside, Err(Error::SniffEntry { .. })));
+}
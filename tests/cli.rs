@@ -1,4 +1,5 @@
 use assert_cmd::Command;
+use predicates::prelude::*;
 use predicates::str::contains;
 use std::fs;
 use std::path::Path;
@@ -7,7 +8,7 @@ use std::path::Path;
 fn test_cli_output_stdout() {
     Command::cargo_bin("ygg")
         .unwrap()
-        .args(&["--show", "rs", "--only", "src"])
+        .args(["--show", "rs", "--only", "src"])
         .assert()
         .success()
         .stdout(contains("📄 Files"));
@@ -20,7 +21,7 @@ fn test_markdown_output_to_file() {
 
     Command::cargo_bin("ygg")
         .unwrap()
-        .args(&["--show", "rs", "--only", "src", "--contents", "--out"])
+        .args(["--show", "rs", "--only", "src", "--contents", "--out"])
         .arg(&outfile)
         .assert()
         .success();
@@ -34,33 +35,998 @@ fn test_markdown_output_to_file() {
 
 #[test]
 fn test_against_fixture() {
-    let fixture_path = Path::new("tests/fixtures/expected_show.txt");
+    // a fixed tree, so the expected index does not change with the crate's own sources
+    let tree = Path::new("tests/fixtures/tree");
     let tmpdir = tempfile::tempdir().unwrap();
     let outfile = tmpdir.path().join("out.txt"); // CLI formatter mode
 
-    // Run CLI
     Command::cargo_bin("ygg")
         .unwrap()
-        .args(&["--show", "rs", "--only", "src", "--no-lines", "--out"])
+        .current_dir(tree)
+        .args(["--show", "rs", "--only", "src", "--no-lines", "--out"])
         .arg(&outfile)
         .assert()
         .success();
 
     let got = fs::read_to_string(&outfile).unwrap();
+    let expected = fs::read_to_string("tests/fixtures/expected_show.txt").unwrap();
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn test_verify_detects_drift_and_tampering() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+    fs::write(root.join("b.rs"), "fn b() {}\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["--show", "rs", "--printed", "CODEX.md"])
+        .assert()
+        .success();
+
+    let codex = fs::read_to_string(root.join("CODEX.md")).unwrap();
+    assert!(codex.contains("codex_hash: "));
+    assert!(codex.contains("hash=\""));
+
+    // untouched tree verifies cleanly
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["verify", "CODEX.md"])
+        .assert()
+        .success()
+        .stdout(contains("intact"));
+
+    // drift in the working tree
+    fs::write(root.join("a.rs"), "fn a() { todo!() }\n").unwrap();
+    fs::remove_file(root.join("b.rs")).unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["verify", "CODEX.md"])
+        .assert()
+        .failure()
+        .stdout(contains("(drifted)"))
+        .stdout(contains("(missing)"));
+
+    // tampering with the codex itself
+    fs::write(root.join("CODEX.md"), codex.replace("fn b() {}", "fn b() { evil() }")).unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["verify", "CODEX.md"])
+        .assert()
+        .failure()
+        .stdout(contains("TAMPERED"));
+}
+
+#[test]
+fn test_snap_log_show_replays_past_contents() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir(root.join("src")).unwrap();
+    fs::write(root.join("src/a.rs"), "fn original() {}\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["snap", "--only", "src", "-m", "first cut"])
+        .assert()
+        .success()
+        .stdout(contains("recorded"));
+
+    fs::write(root.join("src/a.rs"), "fn rewritten() {}\n").unwrap();
+
+    let log = Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .arg("log")
+        .assert()
+        .success()
+        .stdout(contains("first cut"))
+        .get_output()
+        .stdout
+        .clone();
+
+    let log = String::from_utf8(log).unwrap();
+    let id = log
+        .lines()
+        .find(|l| l.contains("first cut"))
+        .and_then(|l| l.split_whitespace().next())
+        .unwrap()
+        .to_string();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["show", &id, "--contents"])
+        .assert()
+        .success()
+        .stdout(contains("fn original() {}"));
+}
+
+#[test]
+fn test_snap_fails_without_recording() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["snap", "--only", "missing"])
+        .assert()
+        .code(2)
+        .stderr(contains("nothing selected"));
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .arg("log")
+        .assert()
+        .success()
+        .stdout(contains("No snapshots recorded yet"));
+}
+
+#[test]
+fn test_stats_cache_tracks_changes_and_can_be_disabled() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    let cache_home = tempfile::tempdir().unwrap();
+    let ygg = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("ygg").unwrap();
+        cmd.current_dir(root).env("XDG_CACHE_HOME", cache_home.path()).args(args);
+        cmd
+    };
+    let cached = || fs::read_dir(cache_home.path().join("ygg")).map_or(0, |d| d.count());
+    fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+
+    ygg(&["--show", "rs", "--no-cache"]).assert().success();
+    assert_eq!(cached(), 0);
+
+    ygg(&["--show", "rs"]).assert().success().stdout(contains("Total LOC: 1"));
+    assert_eq!(cached(), 1);
+    // the project itself is left untouched
+    assert!(!root.join(".ygg").exists());
+
+    // a changed file must not be served from the cache
+    fs::write(root.join("a.rs"), "fn a() {}\nfn b() {}\nfn c() {}\n").unwrap();
+    ygg(&["--show", "rs"]).assert().success().stdout(contains("Total LOC: 3"));
+
+    ygg(&["cache", "--clear"]).assert().success();
+    let dir = fs::read_dir(cache_home.path().join("ygg")).unwrap().next().unwrap().unwrap().path();
+    assert!(!dir.join("stats").exists());
+}
+
+#[test]
+fn test_codex_hashes_the_embedded_text_not_the_cache() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    let cache_home = tempfile::tempdir().unwrap();
+    let ygg = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("ygg").unwrap();
+        cmd.current_dir(root).env("XDG_CACHE_HOME", cache_home.path()).args(args);
+        cmd
+    };
+    let file = root.join("a.rs");
+    fs::write(&file, "fn a() {}\n").unwrap();
+    ygg(&["--show", "rs"]).assert().success();
+
+    // same size, same mtime: the cache still believes the old hash
+    let mtime = fs::metadata(&file).unwrap().modified().unwrap();
+    fs::write(&file, "fn b() {}\n").unwrap();
+    fs::File::options().write(true).open(&file).unwrap().set_modified(mtime).unwrap();
+
+    ygg(&["--show", "rs", "--printed", "CODEX.md"]).assert().success();
+    assert!(fs::read_to_string(root.join("CODEX.md")).unwrap().contains("fn b() {}"));
+    ygg(&["verify", "CODEX.md"]).assert().success().stdout(contains("intact"));
+}
+
+#[test]
+fn test_diff_pairs_directories_by_relative_path() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old/pkg")).unwrap();
+    fs::create_dir_all(root.join("new/pkg")).unwrap();
+    fs::write(root.join("old/pkg/m.py"), "a\nbefore\nc\n").unwrap();
+    fs::write(root.join("new/pkg/m.py"), "a\nafter\nc\n").unwrap();
+    fs::write(root.join("old/same.py"), "same\n").unwrap();
+    fs::write(root.join("new/same.py"), "same\n").unwrap();
+    fs::write(root.join("old/gone.py"), "gone\n").unwrap();
+    fs::write(root.join("new/fresh.py"), "fresh\n").unwrap();
+
+    let output = Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "old", "--", "new"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("- old/gone.py"));
+    assert!(stdout.contains("+ new/fresh.py"));
+    assert!(stdout.contains("old/pkg/m.py → new/pkg/m.py"));
+    assert!(stdout.contains("-before"));
+    assert!(stdout.contains("+after"));
+    assert!(!stdout.contains("same.py"));
+}
+
+#[test]
+fn test_diff_unchanged_files_are_not_moves() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    for side in ["old", "new"] {
+        fs::create_dir_all(root.join(side)).unwrap();
+        fs::write(root.join(side).join("b.py"), "import os\nimport sys\n\ndef b():\n    return os.getcwd()\n").unwrap();
+        fs::write(root.join(side).join("c.py"), "import os\n\ndef c():\n    return os.sep\n").unwrap();
+    }
+    fs::write(root.join("old/d.py"), "x = 1\n").unwrap();
+    fs::write(root.join("new/d.py"), "x = 2\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--stat", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains(" 1 file changed, 1 insertion(+), 1 deletion(-)"))
+        .stdout(contains("moves:").not());
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains("[MOVED]").not());
+}
+
+#[test]
+fn test_list_flags_split_on_commas_and_spaces() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    for side in ["old", "new"] {
+        fs::create_dir_all(root.join(side)).unwrap();
+        fs::write(root.join(side).join("a.py"), format!("a = '{}'\n", side)).unwrap();
+        fs::write(root.join(side).join("b.rs"), format!("// {}\n", side)).unwrap();
+        fs::write(root.join(side).join("c.md"), format!("{}\n", side)).unwrap();
+    }
+
+    for show in ["py,rs", "py rs"] {
+        Command::cargo_bin("ygg")
+            .unwrap()
+            .current_dir(root)
+            .args(["--dir", "new", "--show", show])
+            .assert()
+            .success()
+            .stdout(contains("a.py"))
+            .stdout(contains("b.rs"))
+            .stdout(contains("c.md").not());
+
+        Command::cargo_bin("ygg")
+            .unwrap()
+            .current_dir(root)
+            .args(["diff", "--show", show, "old", "--", "new"])
+            .assert()
+            .code(1)
+            .stdout(contains("a.py"))
+            .stdout(contains("b.rs"))
+            .stdout(contains("c.md").not());
+    }
+}
+
+#[test]
+fn test_diff_fails_on_missing_path() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::write(root.join("a.py"), "a = 1\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "a.py", "--", "missing.py"])
+        .assert()
+        .code(2)
+        .stderr(contains("missing.py: path not found"));
+}
+
+#[test]
+fn test_diff_does_not_pair_lone_files_of_two_directories() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(root.join("old/a.py"), "alpha = 1\n").unwrap();
+    fs::write(root.join("new/b.py"), "something else entirely\n").unwrap();
+
+    let output = Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "old", "--", "new"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("- old/a.py"));
+    assert!(stdout.contains("+ new/b.py"));
+    assert!(!stdout.contains("old/a.py → new/b.py"));
+}
+
+#[test]
+fn test_diff_patch_format() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(root.join("old/m.py"), "1\n2\n3\n4\n5\n6\n7\n8\n").unwrap();
+    fs::write(root.join("new/m.py"), "1\n2\n3\n4\n5\n6\n7\nEIGHT").unwrap();
+    fs::write(root.join("new/n.py"), "new\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--format", "patch", "-U", "1", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains("diff --git a/m.py b/m.py\n--- a/m.py\n+++ b/m.py\n@@ -7,2 +7,2 @@\n 7\n-8\n+EIGHT\n\\ No newline at end of file\n"))
+        .stdout(contains("--- /dev/null\n+++ b/n.py\n"))
+        .stdout(contains("\x1b[").not());
+}
+
+#[test]
+fn test_diff_json_report() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(root.join("old/a.py"), "def f():\n    return 1\n\ndef g():\n    x = 1\n    y = 2\n    return x + y\n").unwrap();
+    fs::write(root.join("new/a.py"), "def f():\n    return 2\n").unwrap();
+    fs::write(root.join("new/b.py"), "def g():\n    x = 1\n    y = 2\n    return x + y\n").unwrap();
+
+    let output = Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--format", "json", "old", "--", "new"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["version"], 1);
+    assert_eq!(report["summary"]["modified"], 1);
+    assert_eq!(report["summary"]["added"], 1);
+
+    let files = report["files"].as_array().unwrap();
+    assert_eq!(files[0]["status"], "modified");
+    assert_eq!(files[0]["path"], "a.py");
+    assert_eq!(files[1]["status"], "added");
+    assert_eq!(files[1]["old_path"], serde_json::Value::Null);
+
+    let lines = files[0]["hunks"][0]["lines"].as_array().unwrap();
+    assert!(lines.iter().any(|l| l["op"] == "+" && l["text"] == "    return 2"));
+
+    let moves = report["moves"].as_array().unwrap();
+    assert_eq!(moves[0]["from_file"], "old/a.py");
+    assert_eq!(moves[0]["to_file"], "new/b.py");
+    assert_eq!(moves[0]["blocks"][0]["kind"], "moved");
+    assert_eq!(moves[0]["blocks"][0]["from"]["end"], 7);
+}
+
+#[test]
+fn test_diff_two_codices() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::write(root.join("a.py"), "def f():\n    return 1\n\ndef g():\n    x = 1\n    y = 2\n    return x + y\n").unwrap();
+
+    // old codex as Markdown, new codex as plain text markers
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["--show", "py", "--printed", "old.md"])
+        .assert()
+        .success();
+
+    fs::write(root.join("a.py"), "def f():\n    return 2\n").unwrap();
+    fs::write(root.join("b.py"), "def g():\n    x = 1\n    y = 2\n    return x + y\n").unwrap();
+
+    let new_txt = Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["--show", "py", "--contents"])
+        .output()
+        .unwrap();
+    fs::write(root.join("new.txt"), &new_txt.stdout).unwrap();
+
+    // the tree on disk no longer matters
+    fs::remove_file(root.join("a.py")).unwrap();
+    fs::remove_file(root.join("b.py")).unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--codex", "old.md", "new.txt"])
+        .assert()
+        .code(1)
+        .stdout(contains("📄 Diff for a.py"))
+        .stdout(contains("+    return 2"))
+        .stdout(contains("+ b.py"))
+        .stdout(contains("a.py → b.py"))
+        .stdout(contains("[MOVED]"));
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--codex", "old.md", "missing.md"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_diff_against_git_revisions() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=ygg", "-c", "user.email=ygg@example.com"])
+            .args(args)
+            .current_dir(root)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    };
+
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/a.py"), "def f():\n    return 1\n\ndef g():\n    x = 1\n    y = 2\n    return x + y\n").unwrap();
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-qm", "one"]);
+
+    // move g() into a new module
+    fs::write(root.join("src/a.py"), "def f():\n    return 1\n").unwrap();
+    fs::write(root.join("src/b.py"), "def g():\n    x = 1\n    y = 2\n    return x + y\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--rev", "HEAD", "src"])
+        .assert()
+        .code(1)
+        .stdout(contains("+ src/b.py"))
+        .stdout(contains("HEAD:src/a.py → src/b.py"))
+        .stdout(contains("[MOVED]"));
+
+    git(&["add", "."]);
+    git(&["commit", "-qm", "two"]);
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--rev", "HEAD~1..HEAD", "--format", "patch"])
+        .assert()
+        .code(1)
+        .stdout(contains("diff --git a/src/a.py b/src/a.py\n"))
+        .stdout(contains("--- /dev/null\n+++ b/src/b.py\n"));
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--rev", "no-such-ref"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_diff_rev_ignores_the_snapshot_store() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=ygg", "-c", "user.email=ygg@example.com"])
+            .args(args)
+            .current_dir(root)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    };
+
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/a.py"), "def f():\n    return 1\n").unwrap();
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-qm", "one"]);
+
+    Command::cargo_bin("ygg").unwrap().current_dir(root).args(["--only", "src"]).assert().success();
+    Command::cargo_bin("ygg").unwrap().current_dir(root).args(["snap", "--only", "src"]).assert().success();
+    assert!(root.join(".ygg").is_dir());
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--rev", "HEAD"])
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn test_diff_detects_renames_and_copies() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    let body = "def g():\n    x = 1\n    y = 2\n    z = 3\n    w = 4\n    return x + y + z + w\n";
+    let other = "def k():\n    a = [1, 2]\n    b = {}\n    c = None\n    return a, b, c\n";
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new/pkg")).unwrap();
+    fs::write(root.join("old/util.py"), body).unwrap();
+    fs::write(root.join("old/keep.py"), other).unwrap();
+    fs::write(root.join("new/keep.py"), other).unwrap();
+    fs::write(root.join("new/pkg/util.py"), body.replace("w = 4", "w = 5")).unwrap();
+    fs::write(root.join("new/clone.py"), other.to_string() + "extra = 1\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "-C", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains("R0"))
+        .stdout(contains(" old/util.py → new/pkg/util.py"))
+        .stdout(contains("+    w = 5"))
+        .stdout(contains("C0"))
+        .stdout(contains(" old/keep.py → new/clone.py"))
+        .stdout(contains("- old/util.py").not());
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--no-renames", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains("- old/util.py"))
+        .stdout(contains("+ new/pkg/util.py"));
+}
+
+#[test]
+fn test_diff_fuzzy_move_match() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(root.join("old/a.py"), "def f():\n    return 1\n\ndef total(items):\n    acc = 0\n    for item in items:\n        acc += item.price\n    return acc\n").unwrap();
+    fs::write(root.join("new/a.py"), "def f():\n    return 1\n").unwrap();
+    fs::write(root.join("new/cart.py"), "class Cart:\n    def total(self, entries):\n        acc = 0\n        for entry in entries:\n            acc += entry.price\n        return acc\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains("[MOVED").not());
+
+    let output = Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--move-match", "identifiers", "--format", "json", "old", "--", "new"])
+        .output()
+        .unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let block = &report["moves"][0]["blocks"][0];
+    assert_eq!(block["from"]["start"], 5);
+    assert_eq!(block["from"]["end"], 8);
+    let sim = block["similarity"].as_u64().unwrap();
+    assert!(sim > 50 && sim < 100, "{}", sim);
+}
+
+#[test]
+fn test_diff_side_by_side() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(root.join("old/a.py"), "def helper(x):\n    y = x * 2\n    return y\n\ndef main():\n    return 0\n").unwrap();
+    fs::write(root.join("new/a.py"), "def main():\n    return 1\n").unwrap();
+    fs::write(root.join("old/b.py"), "import sys\n").unwrap();
+    fs::write(root.join("new/b.py"), "import sys\n\ndef helper(x):\n    y = x * 2\n    return y\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .env("COLUMNS", "100")
+        .args(["diff", "-y", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains("@@ -1,6 +1,2 @@"))
+        .stdout(contains("   6     return 0"))
+        .stdout(contains("|    2     return 1"))
+        .stdout(contains("⇢ [MOVED] to new/b.py:4"))
+        .stdout(contains("⇠ [MOVED] from old/a.py:2"));
+
+    // the side-by-side layout wins over the --out extension
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .env("COLUMNS", "100")
+        .args(["diff", "-y", "--out", "sbs.html", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout("");
+    let written = fs::read_to_string(root.join("sbs.html")).unwrap();
+    assert!(written.contains("|    2     return 1"));
+    assert!(!written.contains("<html"));
+}
+
+#[test]
+fn test_diff_highlights_changed_words() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(root.join("old/notes.md"), "# Notes\nThe total is <b>price</b> + tax.\n").unwrap();
+    fs::write(root.join("new/notes.md"), "# Notes\nThe total is <b>cost</b> + tax.\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--format", "html", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains("<!DOCTYPE html>"))
+        .stdout(contains("<del>&lt;b&gt;price&lt;/b&gt;</del>"))
+        .stdout(contains("<ins>&lt;b&gt;cost&lt;/b&gt;</ins>"));
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "old/notes.md", "--", "new/notes.md"])
+        .assert()
+        .code(1)
+        .stdout(contains("+The total is <b>cost</b> + tax."));
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--format", "html", "--emphasis", "none", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains("<ins>").not());
+}
+
+#[test]
+fn test_diff_hunks_and_ignore_options() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    let old: String = (1..=100).map(|i| format!("value_{} = {}\n", i, i)).collect();
+    let new = old
+        .replace("value_10 = 10\n", "value_10 = 11\n")
+        .replace("value_50 = 50\n", "VALUE_50  =  50\n\n");
+    fs::write(root.join("old.py"), &old).unwrap();
+    fs::write(root.join("new.py"), &new).unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--context", "1", "old.py", "--", "new.py"])
+        .assert()
+        .code(1)
+        .stdout(contains("@@ -9,3 +9,3 @@"))
+        .stdout(contains("+VALUE_50  =  50"))
+        .stdout(contains("value_30").not());
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "-w", "--ignore-case", "--ignore-blank-lines", "old.py", "--", "new.py"])
+        .assert()
+        .code(1)
+        .stdout(contains("+value_10 = 11"))
+        .stdout(contains("VALUE_50").not());
+}
+
+#[test]
+fn test_diff_markdown_report_to_file() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(root.join("old/a.rs"), "fn keep() {}\n\nfn helper() {\n    let x = 1;\n    let y = 2;\n    x + y\n}\n").unwrap();
+    fs::write(root.join("new/a.rs"), "fn keep() {}\n").unwrap();
+    fs::write(root.join("new/b.rs"), "fn other() {}\n\nfn helper() {\n    let x = 1;\n    let y = 2;\n    x + y\n}\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--out", "report.md", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout("");
+
+    let report = fs::read_to_string(root.join("report.md")).unwrap();
+    assert!(report.starts_with("# 📦 Diff Report"));
+    assert!(report.contains("| modified | 1 |"));
+    assert!(report.contains("| added | 1 |"));
+    assert!(report.contains("```diff\n@@ -1,7 +1,1 @@"));
+    assert!(report.contains("## 🔀 Cross-file Moves"));
+    assert!(report.contains("- [MOVED] lines 4–7 → `new/b.rs` lines 4–7\n\n```rust\n   4     let x = 1;\n"));
+    assert!(!report.contains("fn keep() {}\n\nfn helper"));
+    assert!(!report.contains("\x1b["));
+
+    // --format wins over the extension
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--format", "json", "--out", "report.md", "old", "--", "new"])
+        .assert()
+        .code(1);
+    assert!(fs::read_to_string(root.join("report.md")).unwrap().starts_with("{"));
+}
+
+#[test]
+fn test_diff_stat() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(root.join("old/a.rs"), "fn keep() {}\n\nfn helper() {\n    let x = 1;\n    let y = 2;\n    x + y\n}\n").unwrap();
+    fs::write(root.join("new/a.rs"), "fn keep() {}\n").unwrap();
+    fs::write(root.join("new/b.rs"), "fn other() {}\n\nfn helper() {\n    let x = 1;\n    let y = 2;\n    x + y\n}\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--stat", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(" a.rs | 6 ------ ("))
+        .stdout(predicate::str::contains(" b.rs | 7 +++++++ ("))
+        .stdout(predicate::str::contains(" 2 files changed, 7 insertions(+), 6 deletions(-)"))
+        .stdout(predicate::str::contains(" moves: 1 block, "))
+        .stdout(predicate::str::contains("Diff for").not());
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--stat", "--out", "s.md", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout("");
+    let written = fs::read_to_string(root.join("s.md")).unwrap();
+    assert!(written.contains(" 2 files changed, 7 insertions(+), 6 deletions(-)"));
+    assert!(!written.contains("```diff"));
+}
+
+#[test]
+fn test_diff_exit_codes_and_fail_on() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(root.join("old/a.rs"), "fn keep() {}\n\nfn helper() {\n    let x = 1;\n    let y = 2;\n    x + y\n}\n").unwrap();
+    fs::write(root.join("new/a.rs"), "fn keep() {}\n").unwrap();
+    fs::write(root.join("new/b.rs"), "fn other() {}\n\nfn helper() {\n    let x = 1;\n    let y = 2;\n    x + y\n}\n").unwrap();
+
+    let ygg = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("ygg").unwrap();
+        cmd.current_dir(root).args(args);
+        cmd
+    };
+
+    ygg(&["diff", "--quiet", "old", "--", "old"]).assert().success().stdout("");
+    ygg(&["diff", "-q", "old", "--", "new"]).assert().code(1).stdout("").stderr("");
+
+    // the helper only moved: nothing was removed, but `fn other` was added
+    ygg(&["diff", "--fail-on", "removed", "old", "--", "new"])
+        .assert()
+        .success()
+        .stdout(contains("Diff for"));
+    ygg(&["diff", "--fail-on", "added,removed", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stderr(contains("--fail-on added: 1 line added"));
+    ygg(&["diff", "-q", "--fail-on", "moved", "old", "--", "new"]).assert().code(1).stderr("");
+
+    ygg(&["diff", "--rev", "HEAD"]).assert().code(2);
+}
+
+#[test]
+fn test_diff_verify_move_only() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(root.join("old/lib.rs"), "fn keep() {}\n\nfn helper(x: i32) -> i32 {\n    let y = x * 2;\n    y + 1\n}\n").unwrap();
+    fs::write(root.join("new/lib.rs"), "fn keep() {}\n").unwrap();
+    fs::write(root.join("new/util.rs"), "fn helper(x: i32) -> i32 {\n  let y = x * 2;\n  y + 1\n}\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--verify-move-only", "old", "--", "new"])
+        .assert()
+        .success()
+        .stdout(contains("✅ Move-only"))
+        .stdout(contains("Diff for").not());
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--verify-move-only", "--out", "v.json", "old", "--", "new"])
+        .assert()
+        .success()
+        .stdout("");
+    let written = fs::read_to_string(root.join("v.json")).unwrap();
+    assert!(written.contains("✅ Move-only"));
+    assert!(!written.starts_with('{'));
+
+    fs::write(root.join("new/util.rs"), "fn helper(x: i32) -> i32 {\n    let y = x * 3;\n    y + 1\n}\n").unwrap();
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--verify-move-only", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains("❌ Not move-only: 4 lines changed outside moves"))
+        .stdout(contains("- old/lib.rs:4       let y = x * 2;"))
+        .stdout(contains("+ new/util.rs:2      let y = x * 3;"));
+}
+
+#[test]
+fn test_diff_semantic() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::create_dir_all(root.join("new")).unwrap();
+    fs::write(
+        root.join("old/app.py"),
+        "class Cache:\n    def get(self, key):\n        return self.data.get(key, None)\n\ndef load(path):\n    return open(path).read()\n",
+    )
+    .unwrap();
+    fs::write(root.join("new/app.py"), "def load(path, mode='r'):\n    return open(path).read()\n").unwrap();
+    fs::write(
+        root.join("new/cache.py"),
+        "class Cache:\n    def lookup(self, key):\n        return self.data.get(key, None)\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--semantic", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout(contains("  ~ function load  signature changed\n"))
+        .stdout(contains("      + def load(path, mode='r'):"))
+        .stdout(contains("📄 old/app.py → new/cache.py"))
+        .stdout(contains("  ↻ function Cache.get → Cache.lookup  renamed, moved"))
+        .stdout(contains("3 items changed: 0 added, 0 removed, 1 modified, 1 renamed, 1 moved"));
 
-    // If fixture is missing, create it
-    if !fixture_path.exists() {
-        fs::create_dir_all(fixture_path.parent().unwrap()).unwrap();
-        fs::write(fixture_path, &got).unwrap();
-        panic!("Fixture was missing. Created at {:?}. Re-run tests.", fixture_path);
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--semantic", "--out", "items.md", "old", "--", "new"])
+        .assert()
+        .code(1)
+        .stdout("");
+    let written = fs::read_to_string(root.join("items.md")).unwrap();
+    assert!(written.contains("🧬 Semantic diff"));
+    assert!(!written.contains("```diff"));
+}
+
+#[test]
+fn test_diff_tui_needs_a_terminal() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+    fs::write(root.join("b.rs"), "fn b() {}\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--tui", "a.rs", "--", "b.rs"])
+        .assert()
+        .code(2)
+        .stderr(contains("--tui needs an interactive terminal"));
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["diff", "--tui", "--stat", "a.rs", "--", "b.rs"])
+        .assert()
+        .failure()
+        .stderr(contains("cannot be used with"));
+}
+
+#[test]
+fn test_merge_codex_edits() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    fs::write(root.join("a.rs"), "fn a() {\n    let x = 1;\n    x\n}\n\nfn b() {\n    2\n}\n").unwrap();
+    fs::write(root.join("c.rs"), "fn c() {}\n").unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["--show", "rs", "--printed", "base.md"])
+        .assert()
+        .success();
+
+    let base = fs::read_to_string(root.join("base.md")).unwrap();
+    fs::write(root.join("ours.md"), base.replace("let x = 1;", "let x = 10;")).unwrap();
+    fs::write(root.join("theirs.md"), base.replace("    2\n", "    20\n").replace("fn c() {}", "fn c() { 3 }")).unwrap();
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["merge", "base.md", "ours.md", "theirs.md", "--out", "merged.md"])
+        .assert()
+        .success()
+        .stderr(contains("Merged cleanly"));
+
+    let merged = fs::read_to_string(root.join("merged.md")).unwrap();
+    assert!(merged.starts_with("# CODEX"));
+    assert!(merged.contains("let x = 10;"));
+    assert!(merged.contains("    20\n"));
+    assert!(merged.contains("fn c() { 3 }"));
+
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["merge", "base.md", "ours.md", "theirs.md", "--apply"])
+        .assert()
+        .success()
+        .stdout("");
+    assert!(fs::read_to_string(root.join("a.rs")).unwrap().contains("let x = 10;\n    x\n}\n\nfn b() {\n    20\n"));
+    assert_eq!(fs::read_to_string(root.join("c.rs")).unwrap(), "fn c() { 3 }\n");
+
+    // both sides edit the same line
+    fs::write(root.join("theirs.md"), base.replace("let x = 1;", "let x = 2;")).unwrap();
+    Command::cargo_bin("ygg")
+        .unwrap()
+        .current_dir(root)
+        .args(["merge", "base.md", "ours.md", "theirs.md"])
+        .assert()
+        .code(1)
+        .stdout(contains("<<<<<<< ours.md\n    let x = 10;\n=======\n    let x = 2;\n>>>>>>> theirs.md\n"))
+        .stderr(contains("a.rs (1 conflict)"));
+}
+
+#[test]
+fn test_diff_applies_selection_and_gitignore() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path();
+    for side in ["old", "new"] {
+        fs::create_dir_all(root.join(side).join("pkg/__pycache__")).unwrap();
+        fs::create_dir_all(root.join(side).join(".git")).unwrap();
+        fs::write(root.join(side).join(".gitignore"), "__pycache__/\n*.log\n").unwrap();
+        fs::write(root.join(side).join("pkg/a.py"), format!("side = '{}'\n", side)).unwrap();
+        fs::write(root.join(side).join("pkg/__pycache__/a.pyc"), side).unwrap();
+        fs::write(root.join(side).join(".git/HEAD"), side).unwrap();
+        fs::write(root.join(side).join("notes.md"), format!("# {}\n", side)).unwrap();
+        fs::write(root.join(side).join("run.log"), side).unwrap();
     }
+    fs::write(root.join("skip.txt"), "notes.md\n").unwrap();
 
-    let expected = fs::read_to_string(fixture_path).unwrap();
+    let stat = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("ygg").unwrap();
+        cmd.current_dir(root).args(["diff", "--stat"]).args(args).args(["old", "--", "new"]);
+        cmd.assert().code(1)
+    };
 
-    assert!(
-        got.contains(&expected),
-        "Expected fixture content not found in output.\n--- Expected snippet ---\n{}\n--- Got ---\n{}",
-        expected,
-        got
-    );
+    stat(&[])
+        .stdout(contains(" pkg/a.py "))
+        .stdout(contains(" notes.md "))
+        .stdout(contains("2 files changed"))
+        .stdout(contains("a.pyc").not())
+        .stdout(contains(".git").not());
+    stat(&["--no-gitignore"]).stdout(contains("run.log")).stdout(contains("a.pyc"));
+    stat(&["--show", "py"]).stdout(contains("1 file changed")).stdout(contains("pkg/a.py"));
+    stat(&["--black", "skip.txt"]).stdout(contains("notes.md").not());
+    stat(&["--only", "pkg"]).stdout(contains("notes.md").not()).stdout(contains("pkg/a.py"));
 }
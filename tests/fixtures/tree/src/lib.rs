@@ -0,0 +1,5 @@
+mod parse;
+
+pub fn answer() -> u32 {
+    42
+}